@@ -1,15 +1,14 @@
 fn main() {
-    let url = "https://raw.githubusercontent.com/my-cfd-platform/proto-files/main/proto/";
-    ci_utils::sync_and_build_proto_file_with_builder(url, "AccountsManagerGrpcService.proto", |x| {
-        x.type_attribute(
-            ".",
-            "#[derive(serde::Serialize,serde::Deserialize)]"
+    // The protos in `proto/` are ahead of the shared proto-files repository,
+    // so they are compiled from here rather than synced from upstream.
+    tonic_build::configure()
+        .type_attribute(".", "#[derive(serde::Serialize,serde::Deserialize)]")
+        .compile_protos(
+            &[
+                "proto/AccountsManagerGrpcService.proto",
+                "proto/AccountsManagerPersistenceGrpcService.proto",
+            ],
+            &["proto"],
         )
-    });
-    ci_utils::sync_and_build_proto_file_with_builder(url, "AccountsManagerPersistenceGrpcService.proto", |x| {
-        x.type_attribute(
-            ".",
-            "#[derive(serde::Serialize,serde::Deserialize)]"
-        )
-    });
+        .unwrap();
 }
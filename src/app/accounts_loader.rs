@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use service_sdk::async_trait;
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::accounts_manager_persistence::{GetAllAccountsGrpcRequest, GetBalanceLedgerGrpcRequest};
+use crate::{
+    Account, AccountAuditRecord, AccountsManagerPersistenceGrpcClient, BalanceLedgerEntry,
+    BonusGrant, PersistenceLoadsSettingsModel, SettingsReader,
+};
+
+/// Source of the persisted state the caches are filled with on startup.
+#[async_trait::async_trait]
+pub trait AccountsLoader: Send + Sync {
+    async fn load_accounts(&self, account_type: &str) -> Vec<Account>;
+
+    async fn load_audit_records(&self, account_type: &str) -> Vec<AccountAuditRecord>;
+
+    /// Ledger entries dated at or after `date_from`, plus the last earlier
+    /// entry of every account to start its chain from.
+    async fn load_ledger(&self, account_type: &str, date_from: u64) -> Vec<BalanceLedgerEntry>;
+
+    /// Cut-offs whose closing balances were already published.
+    async fn load_daily_balances_cutoffs(&self, account_type: &str) -> Vec<u64>;
+
+    /// Bonus grants still open, active or earned.
+    async fn load_bonus_grants(&self, account_type: &str) -> Vec<BonusGrant>;
+}
+
+pub struct PersistenceAccountsLoader {
+    settings_reader: Arc<SettingsReader>,
+}
+
+impl PersistenceAccountsLoader {
+    pub fn new(settings_reader: Arc<SettingsReader>) -> Self {
+        Self { settings_reader }
+    }
+
+    /// Whether `load` is turned on, warning when it is not.
+    async fn is_enabled(
+        &self,
+        load: &str,
+        account_type: &str,
+        is_enabled: fn(&PersistenceLoadsSettingsModel) -> bool,
+    ) -> bool {
+        let loads = self
+            .settings_reader
+            .get_settings()
+            .await
+            .persistence_loads
+            .unwrap_or_default();
+
+        if is_enabled(&loads) {
+            return true;
+        }
+
+        println!(
+            "WARNING: persistence_loads.{} is off, {} {} start empty",
+            load, account_type, load
+        );
+        false
+    }
+}
+
+#[async_trait::async_trait]
+impl AccountsLoader for PersistenceAccountsLoader {
+    async fn load_accounts(&self, account_type: &str) -> Vec<Account> {
+        let accounts_persistence_grpc =
+            AccountsManagerPersistenceGrpcClient::new(self.settings_reader.clone());
+
+        let telemetry = MyTelemetryContext::new();
+        telemetry.start_event_tracking("load_accounts");
+
+        let accounts = accounts_persistence_grpc
+            .get_all_accounts(
+                GetAllAccountsGrpcRequest {
+                    accounts_type: account_type.to_string(),
+                },
+                &telemetry,
+            )
+            .await
+            .unwrap();
+
+        match accounts {
+            Some(src) => src.into_iter().map(|x| x.into()).collect(),
+            None => vec![],
+        }
+    }
+
+    async fn load_audit_records(&self, account_type: &str) -> Vec<AccountAuditRecord> {
+        if !self
+            .is_enabled("audit_records", account_type, |x| x.audit_records)
+            .await
+        {
+            return vec![];
+        }
+
+        let accounts_persistence_grpc =
+            AccountsManagerPersistenceGrpcClient::new(self.settings_reader.clone());
+
+        let telemetry = MyTelemetryContext::new();
+        telemetry.start_event_tracking("load_audit_records");
+
+        let records = accounts_persistence_grpc
+            .get_audit_records(
+                GetAllAccountsGrpcRequest {
+                    accounts_type: account_type.to_string(),
+                },
+                &telemetry,
+            )
+            .await
+            .unwrap();
+
+        match records {
+            Some(src) => src.into_iter().map(|x| x.into()).collect(),
+            None => vec![],
+        }
+    }
+
+    async fn load_ledger(&self, account_type: &str, date_from: u64) -> Vec<BalanceLedgerEntry> {
+        if !self
+            .is_enabled("balance_ledger", account_type, |x| x.balance_ledger)
+            .await
+        {
+            return vec![];
+        }
+
+        let accounts_persistence_grpc =
+            AccountsManagerPersistenceGrpcClient::new(self.settings_reader.clone());
+
+        let telemetry = MyTelemetryContext::new();
+        telemetry.start_event_tracking("load_ledger");
+
+        let entries = accounts_persistence_grpc
+            .get_balance_ledger(
+                GetBalanceLedgerGrpcRequest {
+                    accounts_type: account_type.to_string(),
+                    date_from,
+                },
+                &telemetry,
+            )
+            .await
+            .unwrap();
+
+        match entries {
+            Some(src) => src.into_iter().map(|x| x.into()).collect(),
+            None => vec![],
+        }
+    }
+
+    async fn load_daily_balances_cutoffs(&self, account_type: &str) -> Vec<u64> {
+        if !self
+            .is_enabled("daily_balances_cutoffs", account_type, |x| {
+                x.daily_balances_cutoffs
+            })
+            .await
+        {
+            return vec![];
+        }
+
+        let accounts_persistence_grpc =
+            AccountsManagerPersistenceGrpcClient::new(self.settings_reader.clone());
+
+        let telemetry = MyTelemetryContext::new();
+        telemetry.start_event_tracking("load_daily_balances_cutoffs");
+
+        let cutoffs = accounts_persistence_grpc
+            .get_daily_balances_cutoffs(
+                GetAllAccountsGrpcRequest {
+                    accounts_type: account_type.to_string(),
+                },
+                &telemetry,
+            )
+            .await
+            .unwrap();
+
+        match cutoffs {
+            Some(src) => src.into_iter().map(|x| x.date).collect(),
+            None => vec![],
+        }
+    }
+
+    async fn load_bonus_grants(&self, account_type: &str) -> Vec<BonusGrant> {
+        if !self
+            .is_enabled("bonus_grants", account_type, |x| x.bonus_grants)
+            .await
+        {
+            return vec![];
+        }
+
+        let accounts_persistence_grpc =
+            AccountsManagerPersistenceGrpcClient::new(self.settings_reader.clone());
+
+        let telemetry = MyTelemetryContext::new();
+        telemetry.start_event_tracking("load_bonus_grants");
+
+        let grants = accounts_persistence_grpc
+            .get_bonus_grants(
+                GetAllAccountsGrpcRequest {
+                    accounts_type: account_type.to_string(),
+                },
+                &telemetry,
+            )
+            .await
+            .unwrap();
+
+        match grants {
+            Some(src) => src.into_iter().map(|x| x.into()).collect(),
+            None => vec![],
+        }
+    }
+}
@@ -1,55 +1,95 @@
-use std::sync::Arc;
-
-use cfd_engine_sb_contracts::AccountPersistEvent;
-use service_sdk::my_service_bus::abstractions::publisher::MyServiceBusPublisher;
-use service_sdk::my_telemetry::MyTelemetryContext;
-use service_sdk::ServiceContext;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::accounts_manager::AccountManagerUpdateAccountBalanceGrpcResponse;
-use crate::accounts_manager_persistence::GetAllAccountsGrpcRequest;
-use crate::{AccountsCache, ProcessIdCache, SettingsReader};
+use crate::{
+    AccountAuditCache, AccountsCache, AccountsCaches, AccountsLoader, DailyBalancesCache,
+    EventsPublisher, ProcessIdCache, RateLimiter, SbEventsOutbox, SettingsSource,
+    ValidatedSettings, MAX_QUEUED_SB_EVENTS,
+};
 
-use crate::grpc_client::AccountsManagerPersistenceGrpcClient;
 pub struct AppContext {
-    pub accounts_cache: Arc<AccountsCache>,
-    pub settings_reader: Arc<SettingsReader>,
-    pub account_persist_events_publisher: MyServiceBusPublisher<AccountPersistEvent>,
-    pub cache: ProcessIdCache<AccountManagerUpdateAccountBalanceGrpcResponse>
+    pub accounts_caches: AccountsCaches,
+    pub settings: ValidatedSettings,
+    pub events_publisher: Arc<dyn EventsPublisher>,
+    pub sb_events_outbox: Arc<SbEventsOutbox>,
+    pub account_audit_cache: AccountAuditCache,
+    pub daily_balances_cache: DailyBalancesCache,
+    pub cache: ProcessIdCache<AccountManagerUpdateAccountBalanceGrpcResponse>,
+    pub rate_limiter: RateLimiter,
 }
 
 impl AppContext {
-    pub async fn new(settings_reader: Arc<SettingsReader>, sc: &ServiceContext) -> Self {
-        let account_persist_events_publisher = sc.get_sb_publisher(false).await;
-        Self {
-            accounts_cache: Arc::new(load_accounts(settings_reader.clone()).await),
-            settings_reader,
-            account_persist_events_publisher,
-            cache: ProcessIdCache::new()
-        }
-    }
-}
+    pub async fn new(
+        settings_source: Arc<dyn SettingsSource>,
+        accounts_loader: &dyn AccountsLoader,
+        events_publisher: Arc<dyn EventsPublisher>,
+    ) -> Self {
+        let settings = ValidatedSettings::new(settings_source)
+            .await
+            .unwrap_or_else(|errors| panic!("Invalid settings: {}", errors.join("; ")));
+
+        let sb_events_outbox = Arc::new(SbEventsOutbox::new(
+            events_publisher.clone(),
+            MAX_QUEUED_SB_EVENTS,
+        ));
+
+        let mut accounts_caches = vec![];
+        let mut audit_records = vec![];
+        let mut daily_balances_cutoffs = HashMap::new();
 
-async fn load_accounts(settings_reader: Arc<SettingsReader>) -> AccountsCache {
-    let settings = settings_reader.get_settings().await;
-    let accounts_persistence_grpc =
-        AccountsManagerPersistenceGrpcClient::new(settings_reader.clone());
+        for account_type in settings.get_account_types().await {
+            let accounts = accounts_loader.load_accounts(&account_type).await;
 
-    let telemetry = MyTelemetryContext::new();
-    telemetry.start_event_tracking("load_accounts");
+            println!(
+                "Load {} {} accounts from persistence",
+                accounts.len(),
+                account_type
+            );
 
-    let accounts = accounts_persistence_grpc
-        .get_all_accounts(GetAllAccountsGrpcRequest{
-            accounts_type: settings._type,
-        }, &telemetry)
-        .await
-        .unwrap();
+            let retention = settings.get_balance_history_retention().await;
+            let now = chrono::offset::Utc::now().timestamp_millis() as u64;
+            let ledger_entries = accounts_loader
+                .load_ledger(
+                    &account_type,
+                    now.saturating_sub(retention.as_millis() as u64),
+                )
+                .await;
 
-    let accounts = match accounts {
-        Some(src) => src,
-        None => vec![],
-    };
+            let bonus_grants = accounts_loader.load_bonus_grants(&account_type).await;
 
-    println!("Load {} accounts from persistence", accounts.len());
+            let (accounts_cache, mismatches) = AccountsCache::restore(
+                &account_type,
+                accounts,
+                ledger_entries,
+                bonus_grants,
+                sb_events_outbox.clone(),
+            );
 
-    return AccountsCache::new(accounts.iter().map(|x| x.to_owned().into()).collect());
+            for mismatch in mismatches {
+                println!(
+                    "Ledger of {} accounts does not match the snapshot: {:?}",
+                    account_type, mismatch
+                );
+            }
+
+            accounts_caches.push(accounts_cache);
+            audit_records.extend(accounts_loader.load_audit_records(&account_type).await);
+
+            let cutoffs = accounts_loader
+                .load_daily_balances_cutoffs(&account_type)
+                .await;
+            daily_balances_cutoffs.insert(account_type, cutoffs.into_iter().collect());
+        }
+
+        Self {
+            accounts_caches: AccountsCaches::new(&settings.get_env_type().await, accounts_caches),
+            settings,
+            sb_events_outbox,
+            events_publisher,
+            account_audit_cache: AccountAuditCache::new(audit_records),
+            daily_balances_cache: DailyBalancesCache::new(daily_balances_cutoffs),
+            cache: ProcessIdCache::new(),
+            rate_limiter: RateLimiter::new(),
+        }
+    }
 }
@@ -0,0 +1,92 @@
+use cfd_engine_sb_contracts::AccountPersistEvent;
+use service_sdk::async_trait;
+use service_sdk::my_service_bus::abstractions::publisher::MyServiceBusPublisher;
+use service_sdk::my_telemetry::MyTelemetryContext;
+use service_sdk::ServiceContext;
+
+use crate::{AccountChangeSbEvent, DailyBalancesSbEvent};
+
+/// Outgoing account events. Every event carries the account type it belongs
+/// to; errors are returned as text and mapped by the flows.
+#[async_trait::async_trait]
+pub trait EventsPublisher: Send + Sync {
+    async fn publish_persist_event(
+        &self,
+        account_type: &str,
+        event: &AccountPersistEvent,
+        my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String>;
+
+    async fn publish_change_event(
+        &self,
+        account_type: &str,
+        event: &AccountChangeSbEvent,
+        my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String>;
+
+    async fn publish_daily_balances_event(
+        &self,
+        account_type: &str,
+        event: &DailyBalancesSbEvent,
+        my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String>;
+}
+
+pub struct SbEventsPublisher {
+    account_persist_events_publisher: MyServiceBusPublisher<AccountPersistEvent>,
+    account_change_events_publisher: MyServiceBusPublisher<AccountChangeSbEvent>,
+    daily_balances_events_publisher: MyServiceBusPublisher<DailyBalancesSbEvent>,
+}
+
+impl SbEventsPublisher {
+    pub async fn new(sc: &ServiceContext) -> Self {
+        Self {
+            account_persist_events_publisher: sc.get_sb_publisher(false).await,
+            account_change_events_publisher: sc.get_sb_publisher(false).await,
+            daily_balances_events_publisher: sc.get_sb_publisher(false).await,
+        }
+    }
+}
+
+fn get_headers(account_type: &str) -> Vec<(String, String)> {
+    vec![("type".to_string(), account_type.to_string())]
+}
+
+#[async_trait::async_trait]
+impl EventsPublisher for SbEventsPublisher {
+    async fn publish_persist_event(
+        &self,
+        account_type: &str,
+        event: &AccountPersistEvent,
+        my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.account_persist_events_publisher
+            .publish_with_headers(event, get_headers(account_type).into(), Some(my_telemetry))
+            .await
+            .map_err(|err| format!("{:?}", err))
+    }
+
+    async fn publish_change_event(
+        &self,
+        account_type: &str,
+        event: &AccountChangeSbEvent,
+        my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.account_change_events_publisher
+            .publish_with_headers(event, get_headers(account_type).into(), Some(my_telemetry))
+            .await
+            .map_err(|err| format!("{:?}", err))
+    }
+
+    async fn publish_daily_balances_event(
+        &self,
+        account_type: &str,
+        event: &DailyBalancesSbEvent,
+        my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.daily_balances_events_publisher
+            .publish_with_headers(event, get_headers(account_type).into(), Some(my_telemetry))
+            .await
+            .map_err(|err| format!("{:?}", err))
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use cfd_engine_sb_contracts::AccountPersistEvent;
+use service_sdk::async_trait;
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::{
+    Account, AccountAuditRecord, AccountChangeSbEvent, AccountsLoader, BalanceLedgerEntry,
+    BonusGrant, DailyBalancesSbEvent, EventsPublisher, SettingsModel, SettingsSource,
+};
+
+/// Serves a fixed set of accounts per account type, each with its opening
+/// ledger entry only, and no other state.
+#[derive(Default)]
+pub struct InMemoryAccountsLoader {
+    accounts: HashMap<String, Vec<Account>>,
+}
+
+impl InMemoryAccountsLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_accounts(mut self, account_type: &str, accounts: Vec<Account>) -> Self {
+        self.accounts.insert(account_type.to_string(), accounts);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl AccountsLoader for InMemoryAccountsLoader {
+    async fn load_accounts(&self, account_type: &str) -> Vec<Account> {
+        self.accounts.get(account_type).cloned().unwrap_or_default()
+    }
+
+    async fn load_audit_records(&self, _account_type: &str) -> Vec<AccountAuditRecord> {
+        vec![]
+    }
+
+    async fn load_ledger(&self, account_type: &str, _date_from: u64) -> Vec<BalanceLedgerEntry> {
+        match self.accounts.get(account_type) {
+            Some(accounts) => accounts
+                .iter()
+                .map(|x| BalanceLedgerEntry::opening(x, x.create_date))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    async fn load_daily_balances_cutoffs(&self, _account_type: &str) -> Vec<u64> {
+        vec![]
+    }
+
+    async fn load_bonus_grants(&self, _account_type: &str) -> Vec<BonusGrant> {
+        vec![]
+    }
+}
+
+pub struct InMemorySettingsSource {
+    settings: RwLock<SettingsModel>,
+}
+
+impl InMemorySettingsSource {
+    pub fn new(settings: SettingsModel) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+        }
+    }
+
+    /// Takes effect on the next `ValidatedSettings::reload`.
+    pub async fn set(&self, settings: SettingsModel) {
+        *self.settings.write().await = settings;
+    }
+}
+
+#[async_trait::async_trait]
+impl SettingsSource for InMemorySettingsSource {
+    async fn get_settings(&self) -> SettingsModel {
+        self.settings.read().await.clone()
+    }
+}
+
+/// Keeps every published event together with its account type, and can be
+/// switched to fail to simulate a service bus outage.
+#[derive(Default)]
+pub struct InMemoryEventsPublisher {
+    pub persist_events: Mutex<Vec<(String, AccountPersistEvent)>>,
+    pub change_events: Mutex<Vec<(String, AccountChangeSbEvent)>>,
+    pub daily_balances_events: Mutex<Vec<(String, DailyBalancesSbEvent)>>,
+    fail: Mutex<bool>,
+}
+
+impl InMemoryEventsPublisher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn set_fail(&self, fail: bool) {
+        *self.fail.lock().await = fail;
+    }
+
+    async fn check_fail(&self) -> Result<(), String> {
+        if *self.fail.lock().await {
+            return Err("Publisher is disconnected".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsPublisher for InMemoryEventsPublisher {
+    async fn publish_persist_event(
+        &self,
+        account_type: &str,
+        event: &AccountPersistEvent,
+        _my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.check_fail().await?;
+        self.persist_events
+            .lock()
+            .await
+            .push((account_type.to_string(), event.clone()));
+        Ok(())
+    }
+
+    async fn publish_change_event(
+        &self,
+        account_type: &str,
+        event: &AccountChangeSbEvent,
+        _my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.check_fail().await?;
+        self.change_events
+            .lock()
+            .await
+            .push((account_type.to_string(), event.clone()));
+        Ok(())
+    }
+
+    async fn publish_daily_balances_event(
+        &self,
+        account_type: &str,
+        event: &DailyBalancesSbEvent,
+        _my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.check_fail().await?;
+        self.daily_balances_events
+            .lock()
+            .await
+            .push((account_type.to_string(), event.clone()));
+        Ok(())
+    }
+}
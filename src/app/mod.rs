@@ -1,2 +1,15 @@
+mod accounts_loader;
 mod app_context;
+mod events_publisher;
+#[cfg(feature = "test-utils")]
+mod in_memory;
+mod sb_events_outbox;
+mod settings_source;
+
+pub use accounts_loader::*;
 pub use app_context::*;
+pub use events_publisher::*;
+#[cfg(feature = "test-utils")]
+pub use in_memory::*;
+pub use sb_events_outbox::*;
+pub use settings_source::*;
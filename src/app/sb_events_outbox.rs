@@ -0,0 +1,167 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use cfd_engine_sb_contracts::AccountPersistEvent;
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::{
+    observe_sb_publish, set_sb_events_outbox_size, AccountChangeSbEvent, DailyBalancesSbEvent,
+    EventsPublisher, OperationError,
+};
+
+/// Events the outbox holds before changes are refused.
+pub const MAX_QUEUED_SB_EVENTS: usize = 100_000;
+
+/// Event of a change already applied to the caches, or a chunk of captured
+/// closing balances.
+#[derive(Debug, Clone)]
+pub enum OutboxEvent {
+    Persist(Box<AccountPersistEvent>),
+    Change(Box<AccountChangeSbEvent>),
+    DailyBalances(Box<DailyBalancesSbEvent>),
+}
+
+/// Events of applied changes waiting to be published. A change queues its
+/// events while it still holds the write lock of its accounts cache, so the
+/// events of an account are queued in the order its changes were applied.
+/// They are published from the oldest without holding the queue lock, and a
+/// failed one stays queued with everything behind it.
+///
+/// The queue is kept in memory only: events still queued when the process
+/// stops are lost even though their changes were acknowledged, so the
+/// `sb_events_outbox_size` gauge has to be watched. Once `max_queued` events
+/// wait, new changes are refused before they are applied.
+pub struct SbEventsOutbox {
+    events_publisher: Arc<dyn EventsPublisher>,
+    events: Mutex<VecDeque<(String, OutboxEvent)>>,
+    max_queued: usize,
+    /// Held by whoever publishes, so events go out one at a time and in order.
+    publishing: tokio::sync::Mutex<()>,
+    /// Set while the bus refuses events. Changes then leave publishing to
+    /// `SbEventsOutboxJob` rather than each waiting for the bus to time out.
+    publish_failed: AtomicBool,
+}
+
+impl SbEventsOutbox {
+    pub fn new(events_publisher: Arc<dyn EventsPublisher>, max_queued: usize) -> Self {
+        Self {
+            events_publisher,
+            events: Mutex::new(VecDeque::new()),
+            max_queued,
+            publishing: tokio::sync::Mutex::new(()),
+            publish_failed: AtomicBool::new(false),
+        }
+    }
+
+    /// Fails when the queue is full. Checked before a change is applied.
+    pub fn ensure_capacity(&self) -> Result<(), OperationError> {
+        let queued = self.get_queued_count();
+
+        if queued >= self.max_queued {
+            return Err(OperationError::Unavailable(format!(
+                "{} events are waiting for the service bus",
+                queued
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Queues the events of an applied change. Never fails, so an applied
+    /// change always gets its events; the bound is enforced by
+    /// `ensure_capacity` instead.
+    pub fn enqueue(&self, account_type: &str, events: Vec<OutboxEvent>) {
+        let mut queue = self.events.lock().unwrap();
+
+        for event in events {
+            queue.push_back((account_type.to_string(), event));
+        }
+
+        set_sb_events_outbox_size(queue.len());
+    }
+
+    /// Publishes the queue after a change, unless another task is already
+    /// publishing it or the bus failed the last attempt. An error means the
+    /// events stay queued for the next `flush`.
+    pub async fn publish(&self, my_telemetry: &MyTelemetryContext) -> Result<(), String> {
+        loop {
+            if self.publish_failed.load(Ordering::Relaxed) {
+                return Err("the service bus failed the last publish".to_string());
+            }
+
+            let Ok(publishing) = self.publishing.try_lock() else {
+                return Ok(());
+            };
+
+            self.publish_queued(my_telemetry).await?;
+            drop(publishing);
+
+            // An event queued while the lock was about to be released was
+            // left to us by its change.
+            if self.get_queued_count() == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Publishes whatever is queued, waiting for a running publish first.
+    pub async fn flush(&self, my_telemetry: &MyTelemetryContext) -> Result<(), String> {
+        let _publishing = self.publishing.lock().await;
+        self.publish_queued(my_telemetry).await
+    }
+
+    pub fn get_queued_count(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    async fn publish_queued(&self, my_telemetry: &MyTelemetryContext) -> Result<(), String> {
+        loop {
+            let Some((account_type, event)) = self.events.lock().unwrap().front().cloned() else {
+                self.publish_failed.store(false, Ordering::Relaxed);
+                return Ok(());
+            };
+
+            let started = Instant::now();
+
+            let (name, result) = match &event {
+                OutboxEvent::Persist(event) => (
+                    "account_persist",
+                    self.events_publisher
+                        .publish_persist_event(&account_type, event, my_telemetry)
+                        .await,
+                ),
+                OutboxEvent::Change(event) => (
+                    "account_change",
+                    self.events_publisher
+                        .publish_change_event(&account_type, event, my_telemetry)
+                        .await,
+                ),
+                OutboxEvent::DailyBalances(event) => (
+                    "daily_balances",
+                    self.events_publisher
+                        .publish_daily_balances_event(&account_type, event, my_telemetry)
+                        .await,
+                ),
+            };
+
+            observe_sb_publish(name, started, result.is_ok());
+
+            if let Err(err) = result {
+                self.publish_failed.store(true, Ordering::Relaxed);
+                return Err(err);
+            }
+
+            // Only the publishing task removes events, so the front is still
+            // the event just published.
+            let mut queue = self.events.lock().unwrap();
+            queue.pop_front();
+            set_sb_events_outbox_size(queue.len());
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use service_sdk::async_trait;
+
+use crate::{SettingsModel, SettingsReader};
+
+/// Where `ValidatedSettings` takes fresh settings from on load and reload.
+#[async_trait::async_trait]
+pub trait SettingsSource: Send + Sync {
+    async fn get_settings(&self) -> SettingsModel;
+}
+
+#[async_trait::async_trait]
+impl SettingsSource for SettingsReader {
+    async fn get_settings(&self) -> SettingsModel {
+        let read_access = self.settings.read().await;
+        read_access.clone()
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use service_sdk::my_telemetry::MyTelemetryContext;
+use service_sdk::rust_extensions::MyTimerTick;
+
+use crate::{convert_earned_bonus_grants, expire_bonus_grants, AppContext};
+
+/// Takes back bonuses whose grant expired before the turnover was reached and
+/// converts the earned ones to cash.
+pub struct BonusExpiryJob {
+    app: Arc<AppContext>,
+}
+
+impl BonusExpiryJob {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[service_sdk::async_trait::async_trait]
+impl MyTimerTick for BonusExpiryJob {
+    async fn tick(&self) {
+        let now = chrono::offset::Utc::now().timestamp_millis() as u64;
+
+        if let Err(error) = expire_bonus_grants(&self.app, now, &MyTelemetryContext::new()).await {
+            println!("Bonus expiry failed: {}", error.get_message());
+        }
+
+        if let Err(error) = convert_earned_bonus_grants(&self.app, &MyTelemetryContext::new()).await
+        {
+            println!("Bonus conversion failed: {}", error.get_message());
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use service_sdk::my_telemetry::MyTelemetryContext;
+use service_sdk::rust_extensions::MyTimerTick;
+
+use crate::{capture_daily_balances, get_last_eod_cutoff, AppContext};
+
+/// Captures the closing balances once the configured UTC cut-off has passed.
+/// Ticks more often than daily; a cut-off already captured is skipped.
+pub struct EodSnapshotJob {
+    app: Arc<AppContext>,
+}
+
+impl EodSnapshotJob {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[service_sdk::async_trait::async_trait]
+impl MyTimerTick for EodSnapshotJob {
+    async fn tick(&self) {
+        let now = chrono::offset::Utc::now().timestamp_millis() as u64;
+        let date = get_last_eod_cutoff(now, self.app.settings.get_eod_cutoff().await);
+
+        if let Err(error) =
+            capture_daily_balances(&self.app, date, &MyTelemetryContext::new()).await
+        {
+            println!("End-of-day snapshot failed: {}", error.get_message());
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::{collections::HashMap, sync::Arc};
+
+use service_sdk::rust_extensions::MyTimerTick;
+use tokio::sync::Mutex;
+
+use crate::{AppContext, LedgerCheckpoint};
+
+/// Checks the balance ledger entries appended since the previous tick of
+/// every account type and reports accounts whose cached balance does not
+/// match the replay. A full replay is available through `ReplayLedger`.
+pub struct LedgerCheckJob {
+    app: Arc<AppContext>,
+    checkpoints: Mutex<HashMap<String, HashMap<String, LedgerCheckpoint>>>,
+}
+
+impl LedgerCheckJob {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self {
+            app,
+            checkpoints: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[service_sdk::async_trait::async_trait]
+impl MyTimerTick for LedgerCheckJob {
+    async fn tick(&self) {
+        let mut checkpoints = self.checkpoints.lock().await;
+
+        for accounts_cache in self.app.accounts_caches.get_all() {
+            let mismatches = accounts_cache
+                .verify_ledger_since(
+                    checkpoints
+                        .entry(accounts_cache.get_account_type().to_string())
+                        .or_default(),
+                )
+                .await;
+
+            service_sdk::metrics::gauge!(
+                "ledger_mismatches",
+                "type" => accounts_cache.get_account_type().to_string()
+            )
+            .set(mismatches.len() as f64);
+
+            for mismatch in mismatches {
+                println!(
+                    "Ledger check failed for {} accounts: {:?}",
+                    accounts_cache.get_account_type(),
+                    mismatch
+                );
+            }
+        }
+    }
+}
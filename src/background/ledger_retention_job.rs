@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use service_sdk::rust_extensions::MyTimerTick;
+
+use crate::AppContext;
+
+/// Drops balance ledger entries and daily balances older than the configured
+/// retention window.
+pub struct LedgerRetentionJob {
+    app: Arc<AppContext>,
+}
+
+impl LedgerRetentionJob {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[service_sdk::async_trait::async_trait]
+impl MyTimerTick for LedgerRetentionJob {
+    async fn tick(&self) {
+        let retention = self.app.settings.get_balance_history_retention().await;
+        let now = chrono::offset::Utc::now().timestamp_millis() as u64;
+        let before = now.saturating_sub(retention.as_millis() as u64);
+
+        for accounts_cache in self.app.accounts_caches.get_all() {
+            let pruned = accounts_cache.prune_ledger(before).await;
+
+            if pruned > 0 {
+                println!(
+                    "Pruned {} ledger entries of {} accounts",
+                    pruned,
+                    accounts_cache.get_account_type()
+                );
+            }
+        }
+
+        let pruned = self.app.daily_balances_cache.prune(before).await;
+
+        if pruned > 0 {
+            println!("Pruned {} daily balance snapshots", pruned);
+        }
+    }
+}
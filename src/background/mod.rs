@@ -1,7 +1,19 @@
 // mod accounts_sb_persist_bg_job;
+mod bonus_expiry_job;
+mod eod_snapshot_job;
+mod ledger_check_job;
+mod ledger_retention_job;
 mod persist_queue_item;
+mod sb_events_outbox_job;
+mod settings_reload_job;
 // mod persist_sb_queue_job;
 
 // pub use accounts_sb_persist_bg_job::*;
+pub use bonus_expiry_job::*;
+pub use eod_snapshot_job::*;
+pub use ledger_check_job::*;
+pub use ledger_retention_job::*;
 pub use persist_queue_item::*;
+pub use sb_events_outbox_job::*;
+pub use settings_reload_job::*;
 // pub use persist_sb_queue_job::*;
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use service_sdk::my_telemetry::MyTelemetryContext;
+use service_sdk::rust_extensions::MyTimerTick;
+
+use crate::AppContext;
+
+/// Retries the events the service bus did not take when their change was
+/// applied.
+pub struct SbEventsOutboxJob {
+    app: Arc<AppContext>,
+}
+
+impl SbEventsOutboxJob {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[service_sdk::async_trait::async_trait]
+impl MyTimerTick for SbEventsOutboxJob {
+    async fn tick(&self) {
+        if let Err(error) = self
+            .app
+            .sb_events_outbox
+            .flush(&MyTelemetryContext::new())
+            .await
+        {
+            println!(
+                "{} events are still queued for publishing: {}",
+                self.app.sb_events_outbox.get_queued_count(),
+                error
+            );
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use service_sdk::rust_extensions::MyTimerTick;
+
+use crate::AppContext;
+
+pub struct SettingsReloadJob {
+    app: Arc<AppContext>,
+}
+
+impl SettingsReloadJob {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[service_sdk::async_trait::async_trait]
+impl MyTimerTick for SettingsReloadJob {
+    async fn tick(&self) {
+        self.app.settings.reload().await;
+    }
+}
@@ -0,0 +1,75 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use accounts_manager::{
+    accounts_manager::accounts_manager_grpc_service_server::AccountsManagerGrpcServiceServer,
+    accounts_manager_persistence::accounts_manager_persistence_grpc_service_server::AccountsManagerPersistenceGrpcServiceServer,
+    AppContext, FileAccountsStore, FileSettingsSource, GrpcService, LocalEventsPublisher,
+    LocalPersistenceGrpcService,
+};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:5001";
+
+/// Runs the accounts manager without the platform: accounts are loaded from
+/// and persisted to a JSON file through the in-process bus, and the same file
+/// is served as the persistence gRPC service. Background jobs do not run.
+///
+/// Usage: local_accounts_manager <settings.json> <accounts.json> [address]
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let (settings_path, store_path) = match args.as_slice() {
+        [settings_path, store_path, ..] => (settings_path, store_path),
+        _ => exit("Usage: local_accounts_manager <settings.json> <accounts.json> [address]"),
+    };
+
+    let address: SocketAddr = match args.get(2).map_or(DEFAULT_ADDRESS, |x| x.as_str()).parse() {
+        Ok(address) => address,
+        Err(err) => exit(&format!("Invalid address: {}", err)),
+    };
+
+    let settings_source = match FileSettingsSource::open(settings_path).await {
+        Ok(settings_source) => settings_source,
+        Err(err) => exit(&format!("Can not read settings: {}", err)),
+    };
+
+    let store = match FileAccountsStore::open(store_path).await {
+        Ok(store) => Arc::new(store),
+        Err(err) => exit(&format!("Can not open the accounts store: {}", err)),
+    };
+
+    let (events_publisher, persist_events) = LocalEventsPublisher::new();
+
+    let app = Arc::new(
+        AppContext::new(
+            Arc::new(settings_source),
+            store.as_ref(),
+            Arc::new(events_publisher),
+        )
+        .await,
+    );
+
+    tokio::spawn({
+        let store = store.clone();
+        async move { store.consume(persist_events).await }
+    });
+
+    println!("Local accounts manager listens on {}", address);
+
+    let result = tonic::transport::Server::builder()
+        .add_service(AccountsManagerGrpcServiceServer::new(GrpcService::new(app)))
+        .add_service(AccountsManagerPersistenceGrpcServiceServer::new(
+            LocalPersistenceGrpcService::new(store),
+        ))
+        .serve(address)
+        .await;
+
+    if let Err(err) = result {
+        exit(&format!("gRPC server stopped: {}", err));
+    }
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
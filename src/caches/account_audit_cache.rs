@@ -0,0 +1,172 @@
+use std::collections::{HashMap, VecDeque};
+
+use cfd_engine_sb_contracts::AccountAuditRecordSbModel;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    accounts_manager::AccountMetadataItemGrpcModel,
+    accounts_manager_persistence::PersistenceAuditRecordGrpcModel, AccountChange,
+    AccountTradingDisabledReason, OperationContext,
+};
+
+const MAX_AUDIT_RECORDS_PER_ACCOUNT: usize = 1_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountAuditRecord {
+    pub account_id: String,
+    pub trader_id: String,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+    pub process_id: String,
+    pub operator_id: Option<String>,
+    pub date: u64,
+    pub operation_id: String,
+}
+
+impl Into<AccountAuditRecordSbModel> for AccountAuditRecord {
+    fn into(self) -> AccountAuditRecordSbModel {
+        AccountAuditRecordSbModel {
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            field: self.field,
+            old_value: self.old_value,
+            new_value: self.new_value,
+            process_id: self.process_id,
+            operator_id: self.operator_id,
+            date_time_unix_ms: self.date,
+            operation_id: self.operation_id,
+        }
+    }
+}
+
+impl AccountAuditRecord {
+    /// One record per field that differs between the two account states.
+    pub fn from_change(change: &AccountChange, context: &OperationContext) -> Vec<Self> {
+        let before = &change.before;
+        let after = &change.after;
+        let mut result = vec![];
+
+        let mut add = |field: &str, old_value: String, new_value: String| {
+            if old_value != new_value {
+                result.push(Self {
+                    account_id: after.id.clone(),
+                    trader_id: after.trader_id.clone(),
+                    field: field.to_string(),
+                    old_value,
+                    new_value,
+                    process_id: after.last_update_process_id.clone(),
+                    operator_id: context.operator_id.clone(),
+                    date: after.last_update_date,
+                    operation_id: context.operation_id.clone(),
+                });
+            }
+        };
+
+        add(
+            "trading_group",
+            before.trading_group.clone(),
+            after.trading_group.clone(),
+        );
+        add(
+            "trading_disabled",
+            before.trading_disabled.to_string(),
+            after.trading_disabled.to_string(),
+        );
+        add(
+            "trading_disabled_reasons",
+            format_reasons(&before.trading_disabled_reasons),
+            format_reasons(&after.trading_disabled_reasons),
+        );
+        add(
+            "metadata",
+            format_metadata(&before.metadata),
+            format_metadata(&after.metadata),
+        );
+
+        result
+    }
+}
+
+impl Into<AccountAuditRecord> for PersistenceAuditRecordGrpcModel {
+    fn into(self) -> AccountAuditRecord {
+        AccountAuditRecord {
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            field: self.field,
+            old_value: self.old_value,
+            new_value: self.new_value,
+            process_id: self.process_id,
+            operator_id: self.operator_id,
+            date: self.date,
+            operation_id: self.operation_id,
+        }
+    }
+}
+
+fn format_reasons(reasons: &[AccountTradingDisabledReason]) -> String {
+    reasons
+        .iter()
+        .map(|x| x.reason.as_str_name())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn format_metadata(metadata: &[AccountMetadataItemGrpcModel]) -> String {
+    metadata
+        .iter()
+        .map(|x| format!("{}={}", x.key, x.value))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Latest audit records per account id, oldest first. Filled from
+/// persistence on startup, so it keeps the history of earlier runs.
+pub struct AccountAuditCache {
+    records: RwLock<HashMap<String, VecDeque<AccountAuditRecord>>>,
+}
+
+impl AccountAuditCache {
+    pub fn new(records: Vec<AccountAuditRecord>) -> Self {
+        let mut result = HashMap::new();
+
+        for record in records {
+            add_record(&mut result, record);
+        }
+
+        Self {
+            records: RwLock::new(result),
+        }
+    }
+
+    pub async fn add(&self, records: Vec<AccountAuditRecord>) {
+        let mut write_access = self.records.write().await;
+
+        for record in records {
+            add_record(&mut write_access, record);
+        }
+    }
+
+    pub async fn get(&self, account_id: &str) -> Vec<AccountAuditRecord> {
+        let read_access = self.records.read().await;
+
+        match read_access.get(account_id) {
+            Some(records) => records.iter().cloned().collect(),
+            None => vec![],
+        }
+    }
+}
+
+fn add_record(
+    records: &mut HashMap<String, VecDeque<AccountAuditRecord>>,
+    record: AccountAuditRecord,
+) {
+    let account_records = records.entry(record.account_id.clone()).or_default();
+
+    account_records.push_back(record);
+
+    if account_records.len() > MAX_AUDIT_RECORDS_PER_ACCOUNT {
+        account_records.pop_front();
+    }
+}
@@ -0,0 +1,181 @@
+use serde::Serialize;
+
+use crate::{
+    accounts_manager::{AccountStatementRowType, UpdateBalanceReason},
+    Account, BalanceLedger, BalanceLedgerEntry, OperationError,
+};
+
+const CSV_HEADER: &str =
+    "row_type,date,reason,delta,balance,comment,reference_transaction_id,process_id,operation_id";
+
+/// One line of a statement. Only operation rows carry a reason and a delta;
+/// opening and closing rows carry the balance at the period bounds.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountStatementRow {
+    pub row_type: AccountStatementRowType,
+    pub date: u64,
+    pub reason: Option<UpdateBalanceReason>,
+    pub delta: f64,
+    pub balance: f64,
+    pub comment: String,
+    pub reference_transaction_id: Option<String>,
+    pub process_id: String,
+    pub operation_id: String,
+}
+
+impl AccountStatementRow {
+    fn balance(row_type: AccountStatementRowType, date: u64, balance: f64) -> Self {
+        Self {
+            row_type,
+            date,
+            reason: None,
+            delta: 0.0,
+            balance,
+            comment: "".to_string(),
+            reference_transaction_id: None,
+            process_id: "".to_string(),
+            operation_id: "".to_string(),
+        }
+    }
+
+    fn operation(entry: &BalanceLedgerEntry) -> Self {
+        Self {
+            row_type: AccountStatementRowType::Operation,
+            date: entry.date,
+            reason: entry.reason,
+            delta: entry.delta,
+            balance: entry.new_balance,
+            comment: entry.comment.clone(),
+            reference_transaction_id: entry.reference_transaction_id.clone(),
+            process_id: entry.process_id.clone(),
+            operation_id: entry.operation_id.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountStatement {
+    pub account_id: String,
+    pub trader_id: String,
+    pub currency: String,
+    pub date_from: u64,
+    pub date_to: u64,
+    pub opening_balance: f64,
+    pub operations: Vec<AccountStatementRow>,
+    pub closing_balance: f64,
+}
+
+impl AccountStatement {
+    /// Takes the operations within `date_from..=date_to` from the ledger. The
+    /// opening balance is the one right before `date_from`, or zero if the
+    /// account did not exist yet. An opening entry within the period, which
+    /// is not an operation, sets the opening balance instead.
+    pub fn new(
+        account: &Account,
+        ledger: &BalanceLedger,
+        date_from: u64,
+        date_to: u64,
+    ) -> Result<Self, OperationError> {
+        let mut opening_balance = match date_from.checked_sub(1) {
+            Some(before) => match ledger.get_entry_at(account, before) {
+                Ok(entry) => entry.new_balance,
+                Err(OperationError::AccountNofFound) => 0.0,
+                Err(error) => return Err(error),
+            },
+            None => 0.0,
+        };
+
+        let mut operations = vec![];
+
+        for entry in ledger
+            .get_entries(&account.id)
+            .iter()
+            .filter(|x| x.date >= date_from && x.date <= date_to)
+        {
+            match entry.reason {
+                Some(_) => operations.push(AccountStatementRow::operation(entry)),
+                None => opening_balance = entry.new_balance,
+            }
+        }
+
+        let closing_balance = match operations.last() {
+            Some(last) => last.balance,
+            None => opening_balance,
+        };
+
+        Ok(Self {
+            account_id: account.id.clone(),
+            trader_id: account.trader_id.clone(),
+            currency: account.currency.clone(),
+            date_from,
+            date_to,
+            opening_balance,
+            operations,
+            closing_balance,
+        })
+    }
+
+    /// Opening row, operation rows and closing row.
+    pub fn rows(&self) -> Vec<AccountStatementRow> {
+        let mut result = Vec::with_capacity(self.operations.len() + 2);
+
+        result.push(AccountStatementRow::balance(
+            AccountStatementRowType::Opening,
+            self.date_from,
+            self.opening_balance,
+        ));
+        result.extend(self.operations.iter().cloned());
+        result.push(AccountStatementRow::balance(
+            AccountStatementRowType::Closing,
+            self.date_to,
+            self.closing_balance,
+        ));
+
+        result
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut result = String::from(CSV_HEADER);
+
+        for row in self.rows() {
+            let fields = [
+                row.row_type.as_str_name().to_string(),
+                row.date.to_string(),
+                row.reason
+                    .map(|x| x.as_str_name().to_string())
+                    .unwrap_or_default(),
+                row.delta.to_string(),
+                row.balance.to_string(),
+                escape_csv(&row.comment),
+                escape_csv(&row.reference_transaction_id.unwrap_or_default()),
+                escape_csv(&row.process_id),
+                escape_csv(&row.operation_id),
+            ];
+
+            result.push('\n');
+            result.push_str(&fields.join(","));
+        }
+
+        result.push('\n');
+        result
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// Quotes the value when needed, and prefixes one a spreadsheet would take
+/// for a formula with `'` so that opening the export does not run it.
+fn escape_csv(value: &str) -> String {
+    let value = match value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        true => format!("'{}", value),
+        false => value.to_string(),
+    };
+
+    if !value.contains([',', '"', '\n', '\r']) {
+        return value;
+    }
+
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
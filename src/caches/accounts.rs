@@ -1,9 +1,17 @@
-use cfd_engine_sb_contracts::{AccountSbMetadataModel, AccountSbModel};
+use cfd_engine_sb_contracts::{
+    AccountSbMetadataModel, AccountSbModel, AccountSbTradingDisabledReasonModel,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    accounts_manager::{AccountGrpcModel, AccountMetadataItemGrpcModel},
-    accounts_manager_persistence::PersistenceAccountGrpcModel,
+    accounts_manager::{
+        AccountGrpcModel, AccountMetadataItemGrpcModel, AccountTradingDisabledReasonGrpcModel,
+        TradingDisabledReason,
+    },
+    accounts_manager_persistence::{
+        PersistenceAccountGrpcModel, PersistenceTradingDisabledReasonGrpcModel,
+    },
+    BalanceBuckets,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,11 +22,66 @@ pub struct Account {
     pub create_date: u64,
     pub last_update_date: u64,
     pub last_update_process_id: String,
+    /// Sum of the `buckets`.
     pub balance: f64,
+    pub buckets: BalanceBuckets,
     pub trading_disabled: bool,
     pub create_process_id: String,
     pub trading_group: String,
     pub metadata: Vec<AccountMetadataItemGrpcModel>,
+    pub trading_disabled_reasons: Vec<AccountTradingDisabledReason>,
+    /// Grows by one with every change, so consumers can order updates that
+    /// arrive out of order. A new account starts at 1.
+    pub version: u64,
+    /// Set by the `AccountsCache` the account is kept in.
+    pub account_type: String,
+}
+
+/// State of an account right before and right after a non-balance change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountChange {
+    pub before: Account,
+    pub after: Account,
+}
+
+impl AccountChange {
+    /// False for a request that left the account as it was.
+    pub fn is_changed(&self) -> bool {
+        self.before.version != self.after.version
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountTradingDisabledReason {
+    pub reason: TradingDisabledReason,
+    pub comment: Option<String>,
+    pub date: u64,
+    pub process_id: String,
+}
+
+impl Account {
+    pub fn has_trading_disabled_reason(&self, reason: TradingDisabledReason) -> bool {
+        self.trading_disabled_reasons
+            .iter()
+            .any(|x| x.reason == reason)
+    }
+
+    /// Trading stays disabled while at least one reason is set, so enabling
+    /// for one reason does not lift a block set for another.
+    pub fn set_trading_disabled(
+        &mut self,
+        trading_disabled: bool,
+        reason: AccountTradingDisabledReason,
+    ) {
+        self.trading_disabled_reasons
+            .retain(|x| x.reason != reason.reason);
+
+        if trading_disabled {
+            self.trading_disabled_reasons.push(reason);
+        }
+
+        self.trading_disabled = !self.trading_disabled_reasons.is_empty();
+    }
 }
 
 impl Into<AccountGrpcModel> for Account {
@@ -35,12 +98,59 @@ impl Into<AccountGrpcModel> for Account {
             trading_group: self.trading_group,
             last_update_process_id: self.last_update_process_id,
             metadata: self.metadata,
+            trading_disabled_reasons: self
+                .trading_disabled_reasons
+                .into_iter()
+                .map(|x| x.into())
+                .collect(),
+            version: self.version,
+            cash_balance: self.buckets.cash,
+            bonus_balance: self.buckets.bonus,
+            credit_balance: self.buckets.credit,
+            account_type: self.account_type,
+            operation_id: None,
+        }
+    }
+}
+
+impl Into<AccountTradingDisabledReasonGrpcModel> for AccountTradingDisabledReason {
+    fn into(self) -> AccountTradingDisabledReasonGrpcModel {
+        AccountTradingDisabledReasonGrpcModel {
+            reason: self.reason as i32,
+            comment: self.comment,
+            date: self.date,
+            process_id: self.process_id,
         }
     }
 }
 
 impl Into<Account> for PersistenceAccountGrpcModel {
     fn into(self) -> Account {
+        let mut trading_disabled_reasons: Vec<AccountTradingDisabledReason> = self
+            .trading_disabled_reasons
+            .into_iter()
+            .map(|x| x.into())
+            .collect();
+
+        // Accounts stored before the reasons existed keep only the flag, so
+        // they come back with an unspecified reason that has to be lifted
+        // explicitly.
+        if self.trading_disabled && trading_disabled_reasons.is_empty() {
+            trading_disabled_reasons.push(AccountTradingDisabledReason {
+                reason: TradingDisabledReason::Unspecified,
+                comment: None,
+                date: self.last_update_date,
+                process_id: self.last_update_process_id.clone(),
+            });
+        }
+
+        // Accounts stored before the buckets existed hold everything as cash.
+        let buckets = BalanceBuckets {
+            cash: self.balance - self.bonus_balance - self.credit_balance,
+            bonus: self.bonus_balance,
+            credit: self.credit_balance,
+        };
+
         Account {
             id: self.id,
             currency: self.currency,
@@ -49,6 +159,7 @@ impl Into<Account> for PersistenceAccountGrpcModel {
             last_update_date: self.last_update_date,
             last_update_process_id: self.last_update_process_id,
             balance: self.balance,
+            buckets,
             trading_disabled: self.trading_disabled,
             create_process_id: self.create_process_id,
             trading_group: self.trading_group,
@@ -60,6 +171,9 @@ impl Into<Account> for PersistenceAccountGrpcModel {
                     value: x.value,
                 })
                 .collect(),
+            trading_disabled_reasons,
+            version: self.version,
+            account_type: String::new(),
         }
     }
 }
@@ -70,7 +184,8 @@ impl Into<AccountSbModel> for Account {
             id: self.id,
             trader_id: self.trader_id,
             currency: self.currency,
-            balance: self.balance,
+            // Consumers of the shared contract read `balance` as cash.
+            balance: self.buckets.cash,
             create_date: self.create_date,
             last_update_date: self.last_update_date,
             trading_disabled: self.trading_disabled,
@@ -85,6 +200,37 @@ impl Into<AccountSbModel> for Account {
                     value: x.value,
                 })
                 .collect(),
+            version: self.version,
+            bonus_balance: self.buckets.bonus,
+            credit_balance: self.buckets.credit,
+            trading_disabled_reasons: self
+                .trading_disabled_reasons
+                .into_iter()
+                .map(|x| x.into())
+                .collect(),
+        }
+    }
+}
+
+impl Into<AccountSbTradingDisabledReasonModel> for AccountTradingDisabledReason {
+    fn into(self) -> AccountSbTradingDisabledReasonModel {
+        AccountSbTradingDisabledReasonModel {
+            reason: self.reason as i32,
+            comment: self.comment,
+            date: self.date,
+            process_id: self.process_id,
+        }
+    }
+}
+
+impl Into<AccountTradingDisabledReason> for PersistenceTradingDisabledReasonGrpcModel {
+    fn into(self) -> AccountTradingDisabledReason {
+        AccountTradingDisabledReason {
+            reason: TradingDisabledReason::try_from(self.reason)
+                .unwrap_or(TradingDisabledReason::Unspecified),
+            comment: self.comment,
+            date: self.date,
+            process_id: self.process_id,
         }
     }
 }
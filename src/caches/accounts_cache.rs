@@ -1,53 +1,108 @@
 use std::collections::HashMap;
-
-use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
-
-use crate::accounts_manager::{AccountsManagerOperationResult, SearchAccounts};
-use crate::Account;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub enum OperationError {
-    TraderNotFound,
-    AccountNofFound,
-    NotEnoughBalance,
-}
-
-impl OperationError {
-    pub fn as_grpc_error(&self) -> i32 {
-        match self {
-            OperationError::TraderNotFound => AccountsManagerOperationResult::TraderNotFound as i32,
-            OperationError::AccountNofFound => {
-                AccountsManagerOperationResult::AccountNotFound as i32
-            }
-            OperationError::NotEnoughBalance => {
-                AccountsManagerOperationResult::NotEnoughBalance as i32
-            }
-        }
-    }
-}
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::accounts_manager::{
+    AccountManagerUpdateAccountBalanceGrpcRequest, AccountManagerUpdateAccountMetadataGrpcRequest,
+    AccountManagerUpdateTradingDisabledGrpcRequest, AccountManagerUpdateTradingGroupGrpcRequest,
+    AccountMetadataItemGrpcModel, BonusGrantStatus, SearchAccounts, TradingDisabledReason,
+    UpdateBalanceReason,
+};
+use crate::{
+    get_balance_delta, observe_accounts_cache_lock_wait, replay_account, verify_since, Account,
+    AccountChange, AccountStatement, AccountTradingDisabledReason, BalanceBuckets, BalanceLedger,
+    BalanceLedgerEntry, BalanceRules, BonusGrant, BonusGrants, DailyBalance, LedgerCheckpoint,
+    LedgerMismatch, OperationError, OutboxEvent, SbEventsOutbox,
+};
 
 pub struct AccountsStore {
     pub accounts: HashMap<String, HashMap<String, Account>>,
+    pub ledger: BalanceLedger,
+    pub bonus_grants: BonusGrants,
 }
 
 impl AccountsStore {
     pub fn new(accounts: Vec<Account>) -> Self {
-        let accounts_len = accounts.len();
         let mut accounts_cache = HashMap::new();
+        let mut ledger = BalanceLedger::new();
 
         for account in accounts {
+            ledger.open(&account);
             accounts_cache
                 .entry(account.trader_id.clone())
                 .or_insert(HashMap::new())
                 .insert(account.id.clone(), account);
         }
 
-        service_sdk::metrics::gauge!("accounts_in_cache").set(accounts_len as f64);
-
         Self {
             accounts: accounts_cache,
+            ledger,
+            bonus_grants: BonusGrants::default(),
+        }
+    }
+
+    /// Restores the store from account snapshots and the persisted ledger,
+    /// taking the balances and buckets from the ledger replay rather than
+    /// from the snapshots. An account whose entries do not chain, or that has
+    /// no entries at all, keeps its snapshot and starts a new history at
+    /// `now`. Returns what did not agree.
+    pub fn rebuild(
+        accounts: Vec<Account>,
+        mut ledger: BalanceLedger,
+        now: u64,
+    ) -> (Self, Vec<LedgerMismatch>) {
+        let mut accounts_cache = HashMap::new();
+        let mut result_ledger = BalanceLedger::new();
+        let mut mismatches = vec![];
+
+        for mut account in accounts {
+            let entries = ledger.take_entries(&account.id);
+
+            match replay_account(&entries) {
+                Ok(_) if entries.is_empty() => result_ledger.open_at(&account, now),
+                Ok(balance) => {
+                    if balance != account.balance {
+                        mismatches.push(LedgerMismatch::BalanceMismatch {
+                            account_id: account.id.clone(),
+                            cached: account.balance,
+                            replayed: balance,
+                        });
+                    }
+
+                    account.balance = balance;
+                    account.buckets = entries.last().unwrap().new_buckets;
+                    result_ledger.insert_entries(&account.id, entries);
+                }
+                Err(sequence) => {
+                    mismatches.push(LedgerMismatch::BrokenChain {
+                        account_id: account.id.clone(),
+                        sequence,
+                    });
+                    result_ledger.open_at(&account, now);
+                }
+            }
+
+            accounts_cache
+                .entry(account.trader_id.clone())
+                .or_insert(HashMap::new())
+                .insert(account.id.clone(), account);
         }
+
+        let result = Self {
+            accounts: accounts_cache,
+            ledger: result_ledger,
+            bonus_grants: BonusGrants::default(),
+        };
+
+        return (result, mismatches);
+    }
+
+    /// Replays the ledger and compares the result with the cached balances.
+    pub fn verify_ledger(&self) -> Vec<LedgerMismatch> {
+        self.ledger
+            .verify(self.accounts.values().flat_map(|x| x.values()))
     }
 
     pub fn get_account(&self, trader_id: &str, accounts_id: &str) -> Option<&Account> {
@@ -83,7 +138,7 @@ impl AccountsStore {
     }
 
     pub fn search(&self, search: &SearchAccounts) -> Option<Vec<&Account>> {
-        let traders_condition = search.trader_ids.len() > 0;
+        let traders_condition = !search.trader_ids.is_empty();
 
         let currency_condition = search.currency.is_some();
         let currency = match &search.currency {
@@ -97,13 +152,13 @@ impl AccountsStore {
         let mut to: i64 = 0;
         match &search.created {
             Some(created) => {
-                created_from_condition = created.from.is_some();
-                created_to_condition = created.to.is_some();
-                if created_from_condition {
-                    from = created.from.unwrap();
+                if let Some(value) = created.from {
+                    created_from_condition = true;
+                    from = value;
                 }
-                if created_to_condition {
-                    to = created.to.unwrap();
+                if let Some(value) = created.to {
+                    created_to_condition = true;
+                    to = value;
                 }
             }
             None => {}
@@ -115,13 +170,13 @@ impl AccountsStore {
         let mut balance_to: i64 = 0;
         match &search.balance {
             Some(balance) => {
-                balance_from_condition = balance.from.is_some();
-                balance_to_condition = balance.to.is_some();
-                if balance_from_condition {
-                    balance_from = balance.from.unwrap();
+                if let Some(value) = balance.from {
+                    balance_from_condition = true;
+                    balance_from = value;
                 }
-                if balance_to_condition {
-                    balance_to = balance.to.unwrap();
+                if let Some(value) = balance.to {
+                    balance_to_condition = true;
+                    balance_to = value;
                 }
             }
             None => {}
@@ -133,6 +188,12 @@ impl AccountsStore {
             None => false,
         };
 
+        let trading_group_condition = search.trading_group.is_some();
+        let trading_group = match &search.trading_group {
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+
         let mut accounts: Vec<&Account> = vec![];
         for (trader_id, trader_accounts) in &self.accounts {
             if traders_condition {
@@ -174,6 +235,12 @@ impl AccountsStore {
                         continue;
                     }
                 }
+
+                if trading_group_condition {
+                    if account.trading_group != trading_group {
+                        continue;
+                    }
+                }
                 accounts.push(account);
             }
         }
@@ -198,120 +265,429 @@ impl AccountsStore {
             .entry(account.trader_id.clone())
             .or_insert(HashMap::new());
         trader_accounts.insert(account.id.clone(), account.clone());
+        self.ledger.open(&account);
 
         return account;
     }
 
-    pub fn update_balace(
+    pub fn add_missing_accounts(
         &mut self,
         trader_id: &str,
-        account_id: &str,
-        delta: f64,
-        process_id: &str,
-        allow_negative_balance: bool,
-    ) -> Result<&Account, OperationError> {
-        let trader_accounts = self.accounts.get_mut(trader_id);
-
-        if let None = trader_accounts {
-            return Err(OperationError::TraderNotFound);
+        accounts: Vec<Account>,
+    ) -> Vec<Account> {
+        if accounts.is_empty() {
+            return vec![];
         }
 
-        let account = trader_accounts.unwrap().get_mut(account_id);
+        let trader_accounts = self
+            .accounts
+            .entry(trader_id.to_string())
+            .or_insert(HashMap::new());
 
-        if let None = account {
-            return Err(OperationError::AccountNofFound);
-        }
+        let mut result = vec![];
 
-        let account = account.unwrap();
+        for account in accounts {
+            // The trading group of an account may change after it was
+            // created, so only the currency identifies a default account.
+            let exists = trader_accounts
+                .values()
+                .any(|x| x.currency == account.currency);
+
+            if !exists {
+                trader_accounts.insert(account.id.clone(), account.clone());
+                result.push(account);
+            }
+        }
 
-        if !allow_negative_balance && account.balance + delta < 0.0 {
-            return Err(OperationError::NotEnoughBalance);
+        for account in &result {
+            self.ledger.open(account);
         }
 
-        account.balance += delta;
-        account.last_update_date = chrono::offset::Utc::now().timestamp_millis() as u64;
-        account.last_update_process_id = process_id.to_string();
+        return result;
+    }
+
+    fn get_account_mut(
+        &mut self,
+        trader_id: &str,
+        account_id: &str,
+    ) -> Result<&mut Account, OperationError> {
+        let Some(trader_accounts) = self.accounts.get_mut(trader_id) else {
+            return Err(OperationError::TraderNotFound);
+        };
+
+        let Some(account) = trader_accounts.get_mut(account_id) else {
+            return Err(OperationError::AccountNofFound);
+        };
 
         return Ok(account);
     }
 
-    pub fn update_trading_disabled(
-        &mut self,
+    /// Fails with a conflict when the caller saw another version than the
+    /// current one. `None` skips the check.
+    pub fn ensure_version(
+        &self,
         trader_id: &str,
         account_id: &str,
-        trading_disabled: bool,
-        process_id: &str,
-    ) -> Result<&Account, OperationError> {
-        let trader_accounts = self.accounts.get_mut(trader_id);
+        expected_version: Option<u64>,
+    ) -> Result<(), OperationError> {
+        let Some(expected_version) = expected_version else {
+            return Ok(());
+        };
 
-        if let None = trader_accounts {
+        let Some(trader_accounts) = self.accounts.get(trader_id) else {
             return Err(OperationError::TraderNotFound);
-        }
-
-        let account = trader_accounts.unwrap().get_mut(account_id);
+        };
 
-        if let None = account {
+        let Some(account) = trader_accounts.get(account_id) else {
             return Err(OperationError::AccountNofFound);
+        };
+
+        if account.version != expected_version {
+            return Err(OperationError::Conflict(format!(
+                "Account {} is at version {}, expected {}",
+                account_id, account.version, expected_version
+            )));
         }
 
-        let account = account.unwrap();
+        Ok(())
+    }
+
+    /// Spreads the delta of the request over the balance buckets by its
+    /// reason and the `rules`.
+    pub fn update_balance_by_reason(
+        &mut self,
+        request: &AccountManagerUpdateAccountBalanceGrpcRequest,
+        rules: &BalanceRules,
+    ) -> Result<&Account, OperationError> {
+        let account = self.get_account_mut(&request.trader_id, &request.account_id)?;
 
-        account.trading_disabled = trading_disabled;
-        account.last_update_date = chrono::offset::Utc::now().timestamp_millis() as u64;
-        account.last_update_process_id = process_id.to_string();
+        let buckets_delta = rules.split_delta(
+            account,
+            request.delta,
+            request.reason(),
+            request.allow_negative_balance,
+        )?;
+
+        apply_balance_delta(
+            account,
+            get_balance_delta(request.reason(), request.delta),
+            &buckets_delta,
+            &request.process_id,
+        );
 
         return Ok(account);
     }
 
+    /// Keeps the bonus grants in step with an applied balance update: a
+    /// positive bonus or voucher opens a grant, a bonus debit or conversion
+    /// closes the grant it references or else reduces the open ones, traded
+    /// volume counts towards the active ones. Returns the grants the update
+    /// changed.
+    pub fn track_bonus_grants(
+        &mut self,
+        request: &AccountManagerUpdateAccountBalanceGrpcRequest,
+        operation_id: &str,
+        bonus_expiration: Duration,
+        now: u64,
+    ) -> Vec<BonusGrant> {
+        match request.reason() {
+            UpdateBalanceReason::Bonus | UpdateBalanceReason::Voucher if request.delta > 0.0 => {
+                let grant = BonusGrant {
+                    id: operation_id.to_string(),
+                    account_id: request.account_id.clone(),
+                    trader_id: request.trader_id.clone(),
+                    amount: request.delta,
+                    granted_at: now,
+                    expires_at: request
+                        .bonus_expires_at
+                        .unwrap_or(now + bonus_expiration.as_millis() as u64),
+                    turnover_required: request.bonus_turnover_required,
+                    turnover: 0.0,
+                    status: BonusGrantStatus::Active,
+                };
+
+                self.bonus_grants.add(grant.clone());
+
+                vec![grant]
+            }
+            UpdateBalanceReason::Bonus
+            | UpdateBalanceReason::Voucher
+            | UpdateBalanceReason::BonusConversion => {
+                let status = match request.reason() {
+                    UpdateBalanceReason::BonusConversion => BonusGrantStatus::Converted,
+                    _ => BonusGrantStatus::Expired,
+                };
+
+                if let Some(grant_id) = &request.reference_transaction_id {
+                    if let Some(grant) =
+                        self.bonus_grants
+                            .close(&request.account_id, grant_id, status)
+                    {
+                        return vec![grant];
+                    }
+                }
+
+                self.bonus_grants
+                    .reduce(&request.account_id, request.delta.abs())
+            }
+            UpdateBalanceReason::TradingResult => match request.trading_volume {
+                Some(trading_volume) => self
+                    .bonus_grants
+                    .add_turnover(&request.account_id, trading_volume),
+                None => vec![],
+            },
+            _ => vec![],
+        }
+    }
+
+    pub fn update_trading_disabled(
+        &mut self,
+        trader_id: &str,
+        account_id: &str,
+        trading_disabled: bool,
+        reason: TradingDisabledReason,
+        comment: Option<String>,
+        process_id: &str,
+    ) -> Result<AccountChange, OperationError> {
+        self.update_account(trader_id, account_id, process_id, |account, now| {
+            if account.has_trading_disabled_reason(reason) == trading_disabled {
+                return false;
+            }
+
+            account.set_trading_disabled(
+                trading_disabled,
+                AccountTradingDisabledReason {
+                    reason,
+                    comment,
+                    date: now,
+                    process_id: process_id.to_string(),
+                },
+            );
+
+            true
+        })
+    }
+
     pub fn update_trading_group(
         &mut self,
         trader_id: &str,
         account_id: &str,
         trading_group: &str,
         process_id: &str,
-    ) -> Result<&Account, OperationError> {
-        let trader_accounts = self.accounts.get_mut(trader_id);
+    ) -> Result<AccountChange, OperationError> {
+        self.update_account(trader_id, account_id, process_id, |account, _| {
+            if account.trading_group == trading_group {
+                return false;
+            }
 
-        if let None = trader_accounts {
-            return Err(OperationError::TraderNotFound);
-        }
+            account.trading_group = trading_group.to_string();
+            true
+        })
+    }
 
-        let account = trader_accounts.unwrap().get_mut(account_id);
+    pub fn update_metadata(
+        &mut self,
+        trader_id: &str,
+        account_id: &str,
+        metadata: Vec<AccountMetadataItemGrpcModel>,
+        process_id: &str,
+    ) -> Result<AccountChange, OperationError> {
+        self.update_account(trader_id, account_id, process_id, |account, _| {
+            if account.metadata == metadata {
+                return false;
+            }
 
-        if let None = account {
-            return Err(OperationError::AccountNofFound);
-        }
+            account.metadata = metadata;
+            true
+        })
+    }
 
-        let account = account.unwrap();
+    /// `update` returns false when the account already is in the requested
+    /// state. The account is then left as it was, version included.
+    fn update_account(
+        &mut self,
+        trader_id: &str,
+        account_id: &str,
+        process_id: &str,
+        update: impl FnOnce(&mut Account, u64) -> bool,
+    ) -> Result<AccountChange, OperationError> {
+        let account = self.get_account_mut(trader_id, account_id)?;
+        let before = account.clone();
+        let now = chrono::offset::Utc::now().timestamp_millis() as u64;
+
+        if !update(account, now) {
+            return Ok(AccountChange {
+                before,
+                after: account.clone(),
+            });
+        }
 
-        account.trading_group = trading_group.to_string();
-        account.last_update_date = chrono::offset::Utc::now().timestamp_millis() as u64;
+        account.last_update_date = now;
         account.last_update_process_id = process_id.to_string();
+        account.version += 1;
 
-        return Ok(account);
+        return Ok(AccountChange {
+            before,
+            after: account.clone(),
+        });
     }
 }
 
+fn apply_balance_delta(
+    account: &mut Account,
+    delta: f64,
+    buckets_delta: &BalanceBuckets,
+    process_id: &str,
+) {
+    account.balance += delta;
+    account.buckets.apply(buckets_delta);
+    account.last_update_date = chrono::offset::Utc::now().timestamp_millis() as u64;
+    account.last_update_process_id = process_id.to_string();
+    account.version += 1;
+}
+
+/// Balance of the account at `date`, `None` when it did not exist yet.
+fn get_daily_balance(
+    ledger: &BalanceLedger,
+    account: &Account,
+    date: u64,
+) -> Result<Option<DailyBalance>, OperationError> {
+    let entry = match ledger.get_entry_at(account, date) {
+        Ok(entry) => entry,
+        Err(OperationError::AccountNofFound) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    Ok(Some(DailyBalance {
+        account_id: account.id.clone(),
+        trader_id: account.trader_id.clone(),
+        currency: account.currency.clone(),
+        balance: entry.new_balance,
+        date,
+    }))
+}
+
+const ACCOUNT_UPDATES_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Accounts of one type. Every change takes `to_events`, which turns the
+/// applied change into its SB events; they are queued in the outbox before
+/// the write lock is released, so they are queued in the order the changes
+/// were applied.
 pub struct AccountsCache {
     pub accounts_store: RwLock<AccountsStore>,
+    account_type: String,
+    account_updates: broadcast::Sender<Account>,
+    sb_events_outbox: Arc<SbEventsOutbox>,
 }
 
 impl AccountsCache {
-    pub fn new(accounts: Vec<Account>) -> Self {
+    pub fn new(
+        account_type: &str,
+        mut accounts: Vec<Account>,
+        sb_events_outbox: Arc<SbEventsOutbox>,
+    ) -> Self {
+        for account in &mut accounts {
+            account.account_type = account_type.to_string();
+        }
+
+        Self::from_store(account_type, AccountsStore::new(accounts), sb_events_outbox)
+    }
+
+    /// Loads the accounts together with their persisted ledger, see
+    /// `AccountsStore::rebuild`, and their open bonus grants.
+    pub fn restore(
+        account_type: &str,
+        mut accounts: Vec<Account>,
+        ledger_entries: Vec<BalanceLedgerEntry>,
+        bonus_grants: Vec<BonusGrant>,
+        sb_events_outbox: Arc<SbEventsOutbox>,
+    ) -> (Self, Vec<LedgerMismatch>) {
+        for account in &mut accounts {
+            account.account_type = account_type.to_string();
+        }
+
+        let now = chrono::offset::Utc::now().timestamp_millis() as u64;
+        let (mut store, mismatches) =
+            AccountsStore::rebuild(accounts, BalanceLedger::from_entries(ledger_entries), now);
+        store.bonus_grants = BonusGrants::new(bonus_grants);
+
+        (
+            Self::from_store(account_type, store, sb_events_outbox),
+            mismatches,
+        )
+    }
+
+    fn from_store(
+        account_type: &str,
+        store: AccountsStore,
+        sb_events_outbox: Arc<SbEventsOutbox>,
+    ) -> Self {
+        let (account_updates, _) = broadcast::channel(ACCOUNT_UPDATES_CHANNEL_CAPACITY);
+
+        let accounts_count: usize = store.accounts.values().map(|x| x.len()).sum();
+        service_sdk::metrics::gauge!("accounts_in_cache", "type" => account_type.to_string())
+            .set(accounts_count as f64);
+
         AccountsCache {
-            accounts_store: RwLock::new(AccountsStore::new(accounts)),
+            accounts_store: RwLock::new(store),
+            account_type: account_type.to_string(),
+            account_updates,
+            sb_events_outbox,
         }
     }
 
+    pub fn get_account_type(&self) -> &str {
+        &self.account_type
+    }
+
+    /// Receives every account after it was added or changed. Subscribe before
+    /// taking a snapshot so no change falls between the two.
+    pub fn subscribe_account_updates(&self) -> broadcast::Receiver<Account> {
+        self.account_updates.subscribe()
+    }
+
+    async fn read_store(&self) -> RwLockReadGuard<'_, AccountsStore> {
+        let started = Instant::now();
+        let result = self.accounts_store.read().await;
+        observe_accounts_cache_lock_wait("read", started);
+        result
+    }
+
+    async fn write_store(&self) -> RwLockWriteGuard<'_, AccountsStore> {
+        let started = Instant::now();
+        let result = self.accounts_store.write().await;
+        observe_accounts_cache_lock_wait("write", started);
+        result
+    }
+
+    fn notify_account_updated(&self, account: &Account) {
+        // Sending only fails when nobody is subscribed.
+        let _ = self.account_updates.send(account.clone());
+    }
+
+    fn enqueue_sb_events(&self, events: Vec<OutboxEvent>) {
+        self.sb_events_outbox.enqueue(&self.account_type, events);
+    }
+
+    pub async fn get_all_accounts(&self) -> Vec<Account> {
+        let accounts_store = self.read_store().await;
+
+        accounts_store
+            .accounts
+            .values()
+            .flat_map(|x| x.values().cloned())
+            .collect()
+    }
+
     pub async fn get_account(&self, trader_id: &str, accounts_id: &str) -> Option<Account> {
-        let accounts_store = self.accounts_store.read().await;
+        let accounts_store = self.read_store().await;
         let account = accounts_store.get_account(trader_id, accounts_id)?.clone();
 
         return Some(account);
     }
 
     pub async fn get_accounts(&self, trader_id: &str) -> Option<Vec<Account>> {
-        let accounts_store = self.accounts_store.read().await;
+        let accounts_store = self.read_store().await;
         let accounts = accounts_store.get_accounts(trader_id)?;
 
         let mut result = vec![];
@@ -324,7 +700,7 @@ impl AccountsCache {
     }
 
     pub async fn get_accounts_by_trading_group(&self, group: &str) -> Option<Vec<Account>> {
-        let accounts_store = self.accounts_store.read().await;
+        let accounts_store = self.read_store().await;
 
         if let Some(accounts) = accounts_store.get_accounts_by_trading_group(group) {
             return Some(accounts.iter().map(|x| x.to_owned().clone()).collect());
@@ -333,7 +709,7 @@ impl AccountsCache {
     }
 
     pub async fn search(&self, search: &SearchAccounts) -> Option<Vec<Account>> {
-        let accounts_store = self.accounts_store.read().await;
+        let accounts_store = self.read_store().await;
         let accounts = accounts_store.search(&search)?;
 
         let mut result = vec![];
@@ -346,70 +722,469 @@ impl AccountsCache {
     }
 
     pub async fn get_trader_id_by_account_id(&self, accounts_id: &str) -> Option<String> {
-        let accounts_store = self.accounts_store.read().await;
+        let accounts_store = self.read_store().await;
         return accounts_store.get_trader_id_by_account_id(accounts_id);
     }
 
-    pub async fn add_account(&self, account: Account) -> Account {
-        let mut accounts_store = self.accounts_store.write().await;
-        service_sdk::metrics::gauge!("accounts_in_cache").increment(1);
-        return accounts_store.add_account(account);
+    pub async fn add_account(
+        &self,
+        mut account: Account,
+        to_events: impl FnOnce(&Account) -> Vec<OutboxEvent>,
+    ) -> Result<Account, OperationError> {
+        account.account_type = self.account_type.clone();
+
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+
+        service_sdk::metrics::gauge!("accounts_in_cache", "type" => self.account_type.clone())
+            .increment(1);
+        let account = accounts_store.add_account(account);
+        self.enqueue_sb_events(to_events(&account));
+        self.notify_account_updated(&account);
+        return Ok(account);
     }
 
-    pub async fn update_balance(
+    pub async fn add_missing_accounts(
         &self,
         trader_id: &str,
-        account_id: &str,
-        delta: f64,
-        process_id: &str,
-        allow_negative_balance: bool,
+        mut accounts: Vec<Account>,
+        to_events: impl Fn(&Account) -> Vec<OutboxEvent>,
+    ) -> Result<Vec<Account>, OperationError> {
+        for account in &mut accounts {
+            account.account_type = self.account_type.clone();
+        }
+
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+
+        let result = accounts_store.add_missing_accounts(trader_id, accounts);
+        service_sdk::metrics::gauge!("accounts_in_cache", "type" => self.account_type.clone())
+            .increment(result.len() as f64);
+
+        for account in &result {
+            self.enqueue_sb_events(to_events(account));
+            self.notify_account_updated(account);
+        }
+
+        return Ok(result);
+    }
+
+    /// Applies the balance update, appends it to the ledger and updates the
+    /// bonus grants under the same lock, so neither misses or reorders a
+    /// movement. A grant opened by the update expires after
+    /// `bonus_expiration` unless the request sets its expiry. `to_events`
+    /// gets the account, the appended entry and the changed grants.
+    pub async fn update_balance(
+        &self,
+        request: &AccountManagerUpdateAccountBalanceGrpcRequest,
+        rules: &BalanceRules,
+        bonus_expiration: Duration,
+        operation_id: &str,
+        to_events: impl FnOnce(&Account, &BalanceLedgerEntry, &[BonusGrant]) -> Vec<OutboxEvent>,
     ) -> Result<Account, OperationError> {
-        let mut accounts_store = self.accounts_store.write().await;
-        let account = accounts_store.update_balace(
-            trader_id,
-            account_id,
-            delta,
-            process_id,
-            allow_negative_balance,
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+        accounts_store.ensure_version(
+            &request.trader_id,
+            &request.account_id,
+            request.expected_version,
         )?;
 
-        return Ok(account.clone());
+        let account = accounts_store
+            .update_balance_by_reason(request, rules)?
+            .clone();
+
+        let entry = accounts_store
+            .ledger
+            .append(&account, request, operation_id)
+            .clone();
+
+        let bonus_grants =
+            accounts_store.track_bonus_grants(request, operation_id, bonus_expiration, entry.date);
+
+        self.enqueue_sb_events(to_events(&account, &entry, &bonus_grants));
+        self.notify_account_updated(&account);
+
+        return Ok(account);
     }
 
-    pub async fn update_trading_disabled(
+    pub async fn get_bonus_grants(&self, account_id: &str) -> Vec<BonusGrant> {
+        let accounts_store = self.read_store().await;
+        accounts_store.bonus_grants.get(account_id)
+    }
+
+    /// Active grants that expire at or before `now`.
+    pub async fn get_expired_bonus_grants(&self, now: u64) -> Vec<BonusGrant> {
+        let accounts_store = self.read_store().await;
+        accounts_store.bonus_grants.get_expired(now)
+    }
+
+    /// Earned grants, still to be converted to cash.
+    pub async fn get_earned_bonus_grants(&self) -> Vec<BonusGrant> {
+        let accounts_store = self.read_store().await;
+        accounts_store.bonus_grants.get_earned()
+    }
+
+    /// Closes an open grant without a balance update, see
+    /// `BonusGrants::close`. The events get the unchanged account.
+    pub async fn close_bonus_grant(
         &self,
         trader_id: &str,
         account_id: &str,
-        trading_disabled: bool,
-        process_id: &str,
-    ) -> Result<Account, OperationError> {
-        let mut accounts_store = self.accounts_store.write().await;
-        let account = accounts_store.update_trading_disabled(
-            trader_id,
-            account_id,
-            trading_disabled,
-            process_id,
-        )?;
+        grant_id: &str,
+        status: BonusGrantStatus,
+        to_events: impl FnOnce(&Account, &BonusGrant) -> Vec<OutboxEvent>,
+    ) -> Result<Option<BonusGrant>, OperationError> {
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+
+        let Some(account) = accounts_store.get_account(trader_id, account_id).cloned() else {
+            return Err(OperationError::AccountNofFound);
+        };
 
-        return Ok(account.clone());
+        let grant = accounts_store
+            .bonus_grants
+            .close(account_id, grant_id, status);
+
+        if let Some(grant) = &grant {
+            self.enqueue_sb_events(to_events(&account, grant));
+        }
+
+        Ok(grant)
     }
 
-    pub async fn update_trading_group(
+    pub async fn get_balance_at(
+        &self,
+        trader_id: &str,
+        account_id: &str,
+        date: u64,
+    ) -> Result<BalanceLedgerEntry, OperationError> {
+        let accounts_store = self.read_store().await;
+
+        let Some(account) = accounts_store.get_account(trader_id, account_id) else {
+            return Err(OperationError::AccountNofFound);
+        };
+
+        let entry = accounts_store.ledger.get_entry_at(account, date)?;
+
+        Ok(entry.clone())
+    }
+
+    pub async fn get_statement(
         &self,
         trader_id: &str,
         account_id: &str,
+        date_from: u64,
+        date_to: u64,
+    ) -> Result<AccountStatement, OperationError> {
+        let accounts_store = self.read_store().await;
+
+        let Some(account) = accounts_store.get_account(trader_id, account_id) else {
+            return Err(OperationError::AccountNofFound);
+        };
+
+        AccountStatement::new(account, &accounts_store.ledger, date_from, date_to)
+    }
+
+    /// Balance of every account as of `date`, taken from the ledger so a late
+    /// capture still sees the balance at that time. Accounts created after
+    /// `date` are skipped; an account whose history at `date` is no longer
+    /// retained fails the whole capture rather than going missing from it.
+    pub async fn get_balances_at(&self, date: u64) -> Result<Vec<DailyBalance>, OperationError> {
+        let accounts_store = self.read_store().await;
+        let mut result = vec![];
+
+        for account in accounts_store.accounts.values().flat_map(|x| x.values()) {
+            if let Some(balance) = get_daily_balance(&accounts_store.ledger, account, date)? {
+                result.push(balance);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Balances of the trader's accounts at each of `dates`, oldest first.
+    /// `account_id` narrows them down to one account; accounts that did not
+    /// exist at a date are skipped, and a date whose history is no longer
+    /// retained fails the query.
+    pub async fn get_trader_balances_at(
+        &self,
+        trader_id: &str,
+        account_id: Option<&str>,
+        dates: &[u64],
+    ) -> Result<Vec<DailyBalance>, OperationError> {
+        let accounts_store = self.read_store().await;
+
+        let Some(accounts) = accounts_store.get_accounts(trader_id) else {
+            return Ok(vec![]);
+        };
+
+        let mut result = vec![];
+
+        for date in dates {
+            for account in &accounts {
+                if account_id.is_some() && account_id != Some(account.id.as_str()) {
+                    continue;
+                }
+
+                if let Some(balance) = get_daily_balance(&accounts_store.ledger, account, *date)? {
+                    result.push(balance);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the number of dropped ledger entries.
+    pub async fn prune_ledger(&self, before: u64) -> usize {
+        let mut accounts_store = self.write_store().await;
+        accounts_store.ledger.prune(before)
+    }
+
+    /// Replays the whole retained ledger. The ledger and the accounts are
+    /// copied out so the replay does not hold the lock.
+    pub async fn verify_ledger(&self) -> (usize, Vec<LedgerMismatch>) {
+        let (ledger, accounts) = {
+            let accounts_store = self.read_store().await;
+            let accounts: Vec<Account> = accounts_store
+                .accounts
+                .values()
+                .flat_map(|x| x.values().cloned())
+                .collect();
+
+            (accounts_store.ledger.clone(), accounts)
+        };
+
+        (accounts.len(), ledger.verify(accounts.iter()))
+    }
+
+    /// Checks only the entries appended after the checkpoints, which are
+    /// advanced for every account that checks out. Only those entries are
+    /// copied under the lock. A checkpoint whose following entries were
+    /// trimmed is dropped and the retained history is replayed instead.
+    pub async fn verify_ledger_since(
+        &self,
+        checkpoints: &mut HashMap<String, LedgerCheckpoint>,
+    ) -> Vec<LedgerMismatch> {
+        let pending: Vec<(
+            String,
+            f64,
+            Option<LedgerCheckpoint>,
+            Vec<BalanceLedgerEntry>,
+        )> = {
+            let accounts_store = self.read_store().await;
+
+            accounts_store
+                .accounts
+                .values()
+                .flat_map(|x| x.values())
+                .map(|account| {
+                    let first_sequence = accounts_store
+                        .ledger
+                        .get_entries(&account.id)
+                        .first()
+                        .map(|x| x.sequence);
+
+                    let checkpoint = checkpoints.get(&account.id).copied().filter(
+                        |x| !matches!(first_sequence, Some(first) if first > x.sequence + 1),
+                    );
+
+                    let entries = accounts_store
+                        .ledger
+                        .get_entries_after(&account.id, checkpoint.map(|x| x.sequence));
+
+                    (
+                        account.id.clone(),
+                        account.balance,
+                        checkpoint,
+                        entries.to_vec(),
+                    )
+                })
+                .collect()
+        };
+
+        let mut next_checkpoints = HashMap::new();
+        let mut result = vec![];
+
+        for (account_id, balance, checkpoint, entries) in pending {
+            match verify_since(&account_id, balance, &entries, checkpoint) {
+                Ok(checkpoint) => {
+                    next_checkpoints.insert(account_id, checkpoint);
+                }
+                Err(mismatch) => {
+                    if let Some(checkpoint) = checkpoint {
+                        next_checkpoints.insert(account_id, checkpoint);
+                    }
+
+                    result.push(mismatch);
+                }
+            }
+        }
+
+        *checkpoints = next_checkpoints;
+
+        result
+    }
+
+    pub async fn bulk_update_trading_group(
+        &self,
+        search: &SearchAccounts,
         trading_group: &str,
         process_id: &str,
-    ) -> Result<Account, OperationError> {
-        let mut accounts_store = self.accounts_store.write().await;
-        let account = accounts_store.update_trading_group(
-            trader_id,
-            account_id,
-            trading_group,
-            process_id,
+        mut to_events: impl FnMut(&AccountChange) -> Vec<OutboxEvent>,
+    ) -> Result<Vec<AccountChange>, OperationError> {
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+        let mut result = vec![];
+
+        let account_ids: Vec<(String, String)> = accounts_store
+            .search(search)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|x| x.trading_group != trading_group)
+            .map(|x| (x.trader_id.clone(), x.id.clone()))
+            .collect();
+
+        for (trader_id, account_id) in account_ids {
+            if let Ok(change) = accounts_store.update_trading_group(
+                &trader_id,
+                &account_id,
+                trading_group,
+                process_id,
+            ) {
+                self.enqueue_sb_events(to_events(&change));
+                self.notify_account_updated(&change.after);
+                result.push(change);
+            }
+        }
+
+        return Ok(result);
+    }
+
+    pub async fn bulk_update_trading_disabled(
+        &self,
+        search: &SearchAccounts,
+        trading_disabled: bool,
+        reason: TradingDisabledReason,
+        comment: Option<String>,
+        process_id: &str,
+        mut to_events: impl FnMut(&AccountChange) -> Vec<OutboxEvent>,
+    ) -> Result<Vec<AccountChange>, OperationError> {
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+        let mut result = vec![];
+
+        let account_ids: Vec<(String, String)> = accounts_store
+            .search(search)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|x| x.has_trading_disabled_reason(reason) != trading_disabled)
+            .map(|x| (x.trader_id.clone(), x.id.clone()))
+            .collect();
+
+        for (trader_id, account_id) in account_ids {
+            if let Ok(change) = accounts_store.update_trading_disabled(
+                &trader_id,
+                &account_id,
+                trading_disabled,
+                reason,
+                comment.clone(),
+                process_id,
+            ) {
+                self.enqueue_sb_events(to_events(&change));
+                self.notify_account_updated(&change.after);
+                result.push(change);
+            }
+        }
+
+        return Ok(result);
+    }
+
+    pub async fn update_trading_disabled(
+        &self,
+        request: &AccountManagerUpdateTradingDisabledGrpcRequest,
+        to_events: impl FnOnce(&AccountChange) -> Vec<OutboxEvent>,
+    ) -> Result<AccountChange, OperationError> {
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+        accounts_store.ensure_version(
+            &request.trader_id,
+            &request.account_id,
+            request.expected_version,
         )?;
 
-        return Ok(account.clone());
+        let change = accounts_store.update_trading_disabled(
+            &request.trader_id,
+            &request.account_id,
+            request.trading_disabled,
+            request.reason(),
+            request.comment.clone(),
+            &request.process_id,
+        )?;
+
+        if change.is_changed() {
+            self.enqueue_sb_events(to_events(&change));
+            self.notify_account_updated(&change.after);
+        }
+
+        return Ok(change);
+    }
+
+    pub async fn update_trading_group(
+        &self,
+        request: &AccountManagerUpdateTradingGroupGrpcRequest,
+        to_events: impl FnOnce(&AccountChange) -> Vec<OutboxEvent>,
+    ) -> Result<AccountChange, OperationError> {
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+        accounts_store.ensure_version(
+            &request.trader_id,
+            &request.account_id,
+            request.expected_version,
+        )?;
+
+        let change = accounts_store.update_trading_group(
+            &request.trader_id,
+            &request.account_id,
+            &request.new_trading_group,
+            &request.process_id,
+        )?;
+
+        if change.is_changed() {
+            self.enqueue_sb_events(to_events(&change));
+            self.notify_account_updated(&change.after);
+        }
+
+        return Ok(change);
+    }
+
+    pub async fn update_metadata(
+        &self,
+        request: &AccountManagerUpdateAccountMetadataGrpcRequest,
+        to_events: impl FnOnce(&AccountChange) -> Vec<OutboxEvent>,
+    ) -> Result<AccountChange, OperationError> {
+        let mut accounts_store = self.write_store().await;
+        self.sb_events_outbox.ensure_capacity()?;
+        accounts_store.ensure_version(
+            &request.trader_id,
+            &request.account_id,
+            request.expected_version,
+        )?;
+
+        let change = accounts_store.update_metadata(
+            &request.trader_id,
+            &request.account_id,
+            request.metadata.clone(),
+            &request.process_id,
+        )?;
+
+        if change.is_changed() {
+            self.enqueue_sb_events(to_events(&change));
+            self.notify_account_updated(&change.after);
+        }
+
+        return Ok(change);
     }
 }
 
@@ -0,0 +1,42 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{AccountsCache, OperationError};
+
+/// One `AccountsCache` per account type hosted by the instance.
+pub struct AccountsCaches {
+    default_account_type: String,
+    caches: HashMap<String, Arc<AccountsCache>>,
+}
+
+impl AccountsCaches {
+    pub fn new(default_account_type: &str, caches: Vec<AccountsCache>) -> Self {
+        Self {
+            default_account_type: default_account_type.to_string(),
+            caches: caches
+                .into_iter()
+                .map(|x| (x.get_account_type().to_string(), Arc::new(x)))
+                .collect(),
+        }
+    }
+
+    pub fn get_all(&self) -> Vec<Arc<AccountsCache>> {
+        self.caches.values().cloned().collect()
+    }
+
+    /// An empty type means the default one, so clients that do not send the
+    /// type keep working against the instance `_type`.
+    pub fn get(&self, account_type: &str) -> Result<Arc<AccountsCache>, OperationError> {
+        let account_type = match account_type {
+            "" => self.default_account_type.as_str(),
+            _ => account_type,
+        };
+
+        match self.caches.get(account_type) {
+            Some(cache) => Ok(cache.clone()),
+            None => Err(OperationError::InvalidRequest(format!(
+                "Account type {} is not hosted by this instance",
+                account_type
+            ))),
+        }
+    }
+}
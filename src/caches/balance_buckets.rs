@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{accounts_manager::UpdateBalanceReason, Account, OperationError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BalanceBucket {
+    Cash,
+    Bonus,
+    Credit,
+}
+
+impl BalanceBucket {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cash" => Some(Self::Cash),
+            "bonus" => Some(Self::Bonus),
+            "credit" => Some(Self::Credit),
+            _ => None,
+        }
+    }
+
+    /// Bucket a delta with the reason lands in. Negative trading results are
+    /// spread by `BalanceRules::trading_loss_order` instead, and a bonus
+    /// conversion takes its delta from the bonus bucket.
+    pub fn for_reason(reason: UpdateBalanceReason) -> Self {
+        match reason {
+            UpdateBalanceReason::Bonus | UpdateBalanceReason::Voucher => Self::Bonus,
+            UpdateBalanceReason::Credit => Self::Credit,
+            UpdateBalanceReason::BonusConversion
+            | UpdateBalanceReason::TradingResult
+            | UpdateBalanceReason::BalanceCorrection
+            | UpdateBalanceReason::Deposit
+            | UpdateBalanceReason::Withdrawal
+            | UpdateBalanceReason::WithdrawalCanceled
+            | UpdateBalanceReason::ToppingUp
+            | UpdateBalanceReason::Dividends => Self::Cash,
+        }
+    }
+}
+
+/// Parts of the account balance. Only cash can be withdrawn; the account
+/// `balance` is always the sum of the three.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BalanceBuckets {
+    pub cash: f64,
+    pub bonus: f64,
+    pub credit: f64,
+}
+
+impl BalanceBuckets {
+    pub fn cash(cash: f64) -> Self {
+        Self {
+            cash,
+            ..Default::default()
+        }
+    }
+
+    pub fn get(&self, bucket: BalanceBucket) -> f64 {
+        match bucket {
+            BalanceBucket::Cash => self.cash,
+            BalanceBucket::Bonus => self.bonus,
+            BalanceBucket::Credit => self.credit,
+        }
+    }
+
+    fn get_mut(&mut self, bucket: BalanceBucket) -> &mut f64 {
+        match bucket {
+            BalanceBucket::Cash => &mut self.cash,
+            BalanceBucket::Bonus => &mut self.bonus,
+            BalanceBucket::Credit => &mut self.credit,
+        }
+    }
+
+    pub fn add(&mut self, bucket: BalanceBucket, delta: f64) {
+        *self.get_mut(bucket) += delta;
+    }
+
+    pub fn apply(&mut self, delta: &BalanceBuckets) {
+        self.cash += delta.cash;
+        self.bonus += delta.bonus;
+        self.credit += delta.credit;
+    }
+
+    pub fn total(&self) -> f64 {
+        self.cash + self.bonus + self.credit
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceRules {
+    /// Buckets a trading loss is taken from, first to last. What is left once
+    /// they are empty goes to cash.
+    pub trading_loss_order: Vec<BalanceBucket>,
+}
+
+impl Default for BalanceRules {
+    fn default() -> Self {
+        Self {
+            trading_loss_order: vec![
+                BalanceBucket::Cash,
+                BalanceBucket::Bonus,
+                BalanceBucket::Credit,
+            ],
+        }
+    }
+}
+
+/// Change of the account balance by an update with the reason: a bonus
+/// conversion only moves its delta between buckets.
+pub fn get_balance_delta(reason: UpdateBalanceReason, delta: f64) -> f64 {
+    match reason {
+        UpdateBalanceReason::BonusConversion => 0.0,
+        _ => delta,
+    }
+}
+
+impl BalanceRules {
+    /// Spreads a balance update over the buckets of the account. Without
+    /// `allow_negative_balance` a trading loss may use the whole balance,
+    /// while any other update may only use the bucket it lands in, so a
+    /// withdrawal is checked against cash only.
+    pub fn split_delta(
+        &self,
+        account: &Account,
+        delta: f64,
+        reason: UpdateBalanceReason,
+        allow_negative_balance: bool,
+    ) -> Result<BalanceBuckets, OperationError> {
+        let mut result = BalanceBuckets::default();
+
+        if reason == UpdateBalanceReason::TradingResult && delta < 0.0 {
+            if !allow_negative_balance && account.balance + delta < 0.0 {
+                return Err(OperationError::NotEnoughBalance);
+            }
+
+            let mut remaining = -delta;
+
+            for bucket in &self.trading_loss_order {
+                let consumed = remaining.min(account.buckets.get(*bucket).max(0.0));
+                result.add(*bucket, -consumed);
+                remaining -= consumed;
+            }
+
+            result.add(BalanceBucket::Cash, -remaining);
+            return Ok(result);
+        }
+
+        // A conversion may never move more than the bonus balance, whatever
+        // `allow_negative_balance` says, or it would mint withdrawable cash.
+        if reason == UpdateBalanceReason::BonusConversion {
+            if account.buckets.bonus < delta {
+                return Err(OperationError::NotEnoughBalance);
+            }
+
+            result.add(BalanceBucket::Bonus, -delta);
+            result.add(BalanceBucket::Cash, delta);
+            return Ok(result);
+        }
+
+        let bucket = BalanceBucket::for_reason(reason);
+
+        if !allow_negative_balance && account.buckets.get(bucket) + delta < 0.0 {
+            return Err(OperationError::NotEnoughBalance);
+        }
+
+        result.add(bucket, delta);
+        Ok(result)
+    }
+}
@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+
+use cfd_engine_sb_contracts::BalanceLedgerEntrySbModel;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accounts_manager::{AccountManagerUpdateAccountBalanceGrpcRequest, UpdateBalanceReason},
+    accounts_manager_persistence::PersistenceBalanceLedgerEntryGrpcModel,
+    get_balance_delta, Account, BalanceBuckets, OperationError,
+};
+
+/// One balance movement of an account. The first entry of every account is
+/// the opening one: it has no reason and carries the balance the account was
+/// created with, or the balance it was loaded with when no history was
+/// persisted for it, as its delta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceLedgerEntry {
+    pub sequence: u64,
+    pub account_id: String,
+    pub trader_id: String,
+    pub previous_balance: f64,
+    pub delta: f64,
+    pub new_balance: f64,
+    pub reason: Option<UpdateBalanceReason>,
+    pub comment: String,
+    pub reference_transaction_id: Option<String>,
+    pub process_id: String,
+    pub operation_id: String,
+    pub date: u64,
+    /// Buckets of the account after the entry.
+    pub new_buckets: BalanceBuckets,
+}
+
+impl BalanceLedgerEntry {
+    pub fn opening(account: &Account, date: u64) -> Self {
+        Self {
+            sequence: 0,
+            account_id: account.id.clone(),
+            trader_id: account.trader_id.clone(),
+            previous_balance: 0.0,
+            delta: account.balance,
+            new_balance: account.balance,
+            reason: None,
+            comment: "".to_string(),
+            reference_transaction_id: None,
+            process_id: account.create_process_id.clone(),
+            operation_id: "".to_string(),
+            date,
+            new_buckets: account.buckets,
+        }
+    }
+}
+
+impl Into<BalanceLedgerEntrySbModel> for BalanceLedgerEntry {
+    fn into(self) -> BalanceLedgerEntrySbModel {
+        BalanceLedgerEntrySbModel {
+            sequence: self.sequence,
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            previous_balance: self.previous_balance,
+            delta: self.delta,
+            new_balance: self.new_balance,
+            reason: self.reason.map(|x| x as i32),
+            comment: self.comment,
+            reference_transaction_id: self.reference_transaction_id,
+            process_id: self.process_id,
+            operation_id: self.operation_id,
+            date_time_unix_ms: self.date,
+            cash_balance: self.new_buckets.cash,
+            bonus_balance: self.new_buckets.bonus,
+            credit_balance: self.new_buckets.credit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerMismatch {
+    /// The entries of the account do not chain: a sequence is skipped or an
+    /// entry does not start from the balance the previous one ended with.
+    BrokenChain {
+        account_id: String,
+        sequence: u64,
+    },
+    MissingEntries {
+        account_id: String,
+    },
+    BalanceMismatch {
+        account_id: String,
+        cached: f64,
+        replayed: f64,
+    },
+}
+
+/// Entries kept in memory per account. Past it the oldest are dropped, in
+/// batches of a tenth so appends stay cheap; the persistence keeps them all.
+pub const MAX_LEDGER_ENTRIES_PER_ACCOUNT: usize = 10_000;
+
+/// Last verified point of an account's history: the incremental check
+/// resumes after it instead of replaying the whole retained window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LedgerCheckpoint {
+    pub sequence: u64,
+    pub balance: f64,
+}
+
+/// Append-only log of balance movements per account id. Old entries are
+/// dropped by `prune`, which keeps the last one before the cutoff so the
+/// balance stays answerable at any point of the retained window.
+#[derive(Debug, Default, Clone)]
+pub struct BalanceLedger {
+    entries: HashMap<String, Vec<BalanceLedgerEntry>>,
+}
+
+impl BalanceLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Groups persisted entries by account, in sequence order. The entries
+    /// are taken as they are; `replay` tells whether they chain.
+    pub fn from_entries(entries: Vec<BalanceLedgerEntry>) -> Self {
+        let mut result = Self::new();
+
+        for entry in entries {
+            result
+                .entries
+                .entry(entry.account_id.clone())
+                .or_default()
+                .push(entry);
+        }
+
+        for entries in result.entries.values_mut() {
+            entries.sort_by_key(|x| x.sequence);
+            trim_entries(entries);
+        }
+
+        result
+    }
+
+    /// Starts the history of a new account at its creation.
+    pub fn open(&mut self, account: &Account) {
+        self.open_at(account, account.create_date);
+    }
+
+    /// Starts the history of an account at `date` with its current balance.
+    pub fn open_at(&mut self, account: &Account, date: u64) {
+        self.push(BalanceLedgerEntry::opening(account, date));
+    }
+
+    /// Records the balance update that brought the account to its current
+    /// balance.
+    pub fn append(
+        &mut self,
+        account: &Account,
+        request: &AccountManagerUpdateAccountBalanceGrpcRequest,
+        operation_id: &str,
+    ) -> &BalanceLedgerEntry {
+        self.push(BalanceLedgerEntry {
+            sequence: 0,
+            account_id: account.id.clone(),
+            trader_id: account.trader_id.clone(),
+            previous_balance: 0.0,
+            delta: get_balance_delta(request.reason(), request.delta),
+            new_balance: account.balance,
+            reason: Some(request.reason()),
+            comment: request.comment.clone(),
+            reference_transaction_id: request.reference_transaction_id.clone(),
+            process_id: account.last_update_process_id.clone(),
+            operation_id: operation_id.to_string(),
+            date: account.last_update_date,
+            new_buckets: account.buckets,
+        })
+    }
+
+    /// Chains the entry to the last one of its account.
+    fn push(&mut self, mut entry: BalanceLedgerEntry) -> &BalanceLedgerEntry {
+        let entries = self.entries.entry(entry.account_id.clone()).or_default();
+
+        if let Some(last) = entries.last() {
+            entry.sequence = last.sequence + 1;
+            entry.previous_balance = last.new_balance;
+        }
+
+        entries.push(entry);
+        trim_entries(entries);
+        entries.last().unwrap()
+    }
+
+    /// Removes the entries of the account from the ledger and returns them.
+    pub fn take_entries(&mut self, account_id: &str) -> Vec<BalanceLedgerEntry> {
+        self.entries.remove(account_id).unwrap_or_default()
+    }
+
+    pub fn insert_entries(&mut self, account_id: &str, entries: Vec<BalanceLedgerEntry>) {
+        self.entries.insert(account_id.to_string(), entries);
+    }
+
+    pub fn get_entries(&self, account_id: &str) -> &[BalanceLedgerEntry] {
+        match self.entries.get(account_id) {
+            Some(entries) => entries,
+            None => &[],
+        }
+    }
+
+    /// Entries of the account after the given sequence, or all retained ones
+    /// when there is none.
+    pub fn get_entries_after(
+        &self,
+        account_id: &str,
+        sequence: Option<u64>,
+    ) -> &[BalanceLedgerEntry] {
+        let entries = self.get_entries(account_id);
+
+        match sequence {
+            Some(sequence) => &entries[entries.partition_point(|x| x.sequence <= sequence)..],
+            None => entries,
+        }
+    }
+
+    /// Last entry applied at or before `date`, i.e. the one whose
+    /// `new_balance` was the balance at that time. Fails with not found
+    /// before the account was created, and with an invalid request before
+    /// the first entry held in memory: the history was pruned, or started at
+    /// load time because none was persisted.
+    pub fn get_entry_at(
+        &self,
+        account: &Account,
+        date: u64,
+    ) -> Result<&BalanceLedgerEntry, OperationError> {
+        let entries = self.get_entries(&account.id);
+
+        if let Some(entry) = entries.iter().rev().find(|x| x.date <= date) {
+            return Ok(entry);
+        }
+
+        match entries.first() {
+            Some(first) if first.sequence > 0 || first.date > account.create_date => {
+                Err(OperationError::InvalidRequest(format!(
+                    "Balance history of account {} is retained from {} only",
+                    account.id, first.date
+                )))
+            }
+            _ => Err(OperationError::AccountNofFound),
+        }
+    }
+
+    /// Drops entries older than `before`, keeping per account the last of
+    /// them as the starting point. Returns the number of dropped entries.
+    pub fn prune(&mut self, before: u64) -> usize {
+        let mut result = 0;
+
+        for entries in self.entries.values_mut() {
+            let old = entries.iter().take_while(|x| x.date < before).count();
+
+            if old > 1 {
+                entries.drain(..old - 1);
+                result += old - 1;
+            }
+        }
+
+        result
+    }
+
+    /// Replays every account from its first entry, checking the chain on the
+    /// way. Returns the balance each account ends up with.
+    pub fn replay(&self) -> Result<HashMap<String, f64>, Vec<LedgerMismatch>> {
+        let mut result = HashMap::new();
+        let mut errors = vec![];
+
+        for (account_id, entries) in &self.entries {
+            match replay_account(entries) {
+                Ok(balance) => {
+                    result.insert(account_id.clone(), balance);
+                }
+                Err(sequence) => errors.push(LedgerMismatch::BrokenChain {
+                    account_id: account_id.clone(),
+                    sequence,
+                }),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(result)
+    }
+
+    /// Compares the replayed balances with the given accounts.
+    pub fn verify<'s>(&self, accounts: impl Iterator<Item = &'s Account>) -> Vec<LedgerMismatch> {
+        let replayed = match self.replay() {
+            Ok(replayed) => replayed,
+            Err(errors) => return errors,
+        };
+
+        let mut result = vec![];
+
+        for account in accounts {
+            match replayed.get(&account.id) {
+                Some(balance) if *balance == account.balance => {}
+                Some(balance) => result.push(LedgerMismatch::BalanceMismatch {
+                    account_id: account.id.clone(),
+                    cached: account.balance,
+                    replayed: *balance,
+                }),
+                None => result.push(LedgerMismatch::MissingEntries {
+                    account_id: account.id.clone(),
+                }),
+            }
+        }
+
+        result
+    }
+}
+
+fn trim_entries(entries: &mut Vec<BalanceLedgerEntry>) {
+    if entries.len() > MAX_LEDGER_ENTRIES_PER_ACCOUNT + MAX_LEDGER_ENTRIES_PER_ACCOUNT / 10 {
+        entries.drain(..entries.len() - MAX_LEDGER_ENTRIES_PER_ACCOUNT);
+    }
+}
+
+/// Returns the final balance, or the sequence of the first entry that does
+/// not chain. A pruned history is replayed from its first retained entry.
+pub fn replay_account(entries: &[BalanceLedgerEntry]) -> Result<f64, u64> {
+    let Some(first) = entries.first() else {
+        return Ok(0.0);
+    };
+
+    let balance = match first.sequence {
+        0 => 0.0,
+        _ => first.previous_balance,
+    };
+
+    replay_from(balance, entries)
+}
+
+fn replay_from(mut balance: f64, entries: &[BalanceLedgerEntry]) -> Result<f64, u64> {
+    let Some(first) = entries.first() else {
+        return Ok(balance);
+    };
+
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.sequence != first.sequence + index as u64
+            || entry.previous_balance != balance
+            || entry.previous_balance + entry.delta != entry.new_balance
+        {
+            return Err(entry.sequence);
+        }
+
+        balance = entry.new_balance;
+    }
+
+    Ok(balance)
+}
+
+/// Checks that the entries following the checkpoint chain and end at
+/// `balance`. Without a checkpoint the entries are the whole retained
+/// history. Returns the checkpoint to resume from next time.
+pub fn verify_since(
+    account_id: &str,
+    balance: f64,
+    entries: &[BalanceLedgerEntry],
+    checkpoint: Option<LedgerCheckpoint>,
+) -> Result<LedgerCheckpoint, LedgerMismatch> {
+    let replayed = match checkpoint {
+        Some(checkpoint) => match entries.first() {
+            Some(first) if first.sequence != checkpoint.sequence + 1 => Err(first.sequence),
+            _ => replay_from(checkpoint.balance, entries),
+        },
+        None if entries.is_empty() => {
+            return Err(LedgerMismatch::MissingEntries {
+                account_id: account_id.to_string(),
+            });
+        }
+        None => replay_account(entries),
+    };
+
+    let replayed = replayed.map_err(|sequence| LedgerMismatch::BrokenChain {
+        account_id: account_id.to_string(),
+        sequence,
+    })?;
+
+    if replayed != balance {
+        return Err(LedgerMismatch::BalanceMismatch {
+            account_id: account_id.to_string(),
+            cached: balance,
+            replayed,
+        });
+    }
+
+    let result = match (entries.last(), checkpoint) {
+        (Some(last), _) => LedgerCheckpoint {
+            sequence: last.sequence,
+            balance: last.new_balance,
+        },
+        (None, Some(checkpoint)) => checkpoint,
+        (None, None) => unreachable!(),
+    };
+
+    Ok(result)
+}
+
+impl Into<BalanceLedgerEntry> for PersistenceBalanceLedgerEntryGrpcModel {
+    fn into(self) -> BalanceLedgerEntry {
+        BalanceLedgerEntry {
+            sequence: self.sequence,
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            previous_balance: self.previous_balance,
+            delta: self.delta,
+            new_balance: self.new_balance,
+            reason: self
+                .reason
+                .and_then(|x| UpdateBalanceReason::try_from(x).ok()),
+            comment: self.comment,
+            reference_transaction_id: self.reference_transaction_id,
+            process_id: self.process_id,
+            operation_id: self.operation_id,
+            date: self.date,
+            new_buckets: BalanceBuckets {
+                cash: self.cash_balance,
+                bonus: self.bonus_balance,
+                credit: self.credit_balance,
+            },
+        }
+    }
+}
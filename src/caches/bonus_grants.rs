@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use cfd_engine_sb_contracts::BonusGrantSbModel;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accounts_manager::BonusGrantStatus,
+    accounts_manager_persistence::PersistenceBonusGrantGrpcModel,
+};
+
+/// Bonus credited by one balance update. It is taken back once it expires
+/// unless the trader earned it by trading `turnover_required` volume first,
+/// in which case it is converted to cash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BonusGrant {
+    /// Operation id of the balance update that credited the bonus.
+    pub id: String,
+    pub account_id: String,
+    pub trader_id: String,
+    pub amount: f64,
+    pub granted_at: u64,
+    pub expires_at: u64,
+    /// A grant without a requirement can not be earned and always expires.
+    pub turnover_required: Option<f64>,
+    pub turnover: f64,
+    pub status: BonusGrantStatus,
+}
+
+impl Into<BonusGrantSbModel> for BonusGrant {
+    fn into(self) -> BonusGrantSbModel {
+        BonusGrantSbModel {
+            id: self.id,
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            amount: self.amount,
+            granted_at: self.granted_at,
+            expires_at: self.expires_at,
+            turnover_required: self.turnover_required,
+            turnover: self.turnover,
+            status: self.status as i32,
+        }
+    }
+}
+
+impl BonusGrant {
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self.status,
+            BonusGrantStatus::Active | BonusGrantStatus::Earned
+        )
+    }
+}
+
+/// Open bonus grants of one account type, active or earned, per account id.
+/// They live in the `AccountsStore`, so a grant changes under the same lock
+/// as the balance update it belongs to. A grant is dropped once it is closed.
+#[derive(Debug, Default)]
+pub struct BonusGrants {
+    grants: HashMap<String, Vec<BonusGrant>>,
+}
+
+impl BonusGrants {
+    /// Takes the persisted grants; closed ones are skipped.
+    pub fn new(grants: Vec<BonusGrant>) -> Self {
+        let mut result: HashMap<String, Vec<BonusGrant>> = HashMap::new();
+
+        for grant in grants {
+            if grant.is_open() {
+                result
+                    .entry(grant.account_id.clone())
+                    .or_default()
+                    .push(grant);
+            }
+        }
+
+        for grants in result.values_mut() {
+            grants.sort_by_key(|x| x.granted_at);
+        }
+
+        Self { grants: result }
+    }
+
+    pub fn add(&mut self, grant: BonusGrant) {
+        self.grants
+            .entry(grant.account_id.clone())
+            .or_default()
+            .push(grant);
+    }
+
+    pub fn get(&self, account_id: &str) -> Vec<BonusGrant> {
+        match self.grants.get(account_id) {
+            Some(grants) => grants.clone(),
+            None => vec![],
+        }
+    }
+
+    /// Counts traded volume towards every active grant of the account, which
+    /// becomes earned once it reaches its requirement. Returns the grants the
+    /// volume counted towards.
+    pub fn add_turnover(&mut self, account_id: &str, volume: f64) -> Vec<BonusGrant> {
+        let mut result = vec![];
+
+        let Some(grants) = self.grants.get_mut(account_id) else {
+            return result;
+        };
+
+        for grant in grants {
+            if grant.status != BonusGrantStatus::Active {
+                continue;
+            }
+
+            grant.turnover += volume;
+
+            if let Some(turnover_required) = grant.turnover_required {
+                if grant.turnover >= turnover_required {
+                    grant.status = BonusGrantStatus::Earned;
+                }
+            }
+
+            result.push(grant.clone());
+        }
+
+        result
+    }
+
+    /// Takes `amount` off the open grants of the account, the oldest first.
+    /// A grant taken down to zero is closed as expired. Returns the grants
+    /// it changed.
+    pub fn reduce(&mut self, account_id: &str, amount: f64) -> Vec<BonusGrant> {
+        let mut result = vec![];
+
+        let Some(grants) = self.grants.get_mut(account_id) else {
+            return result;
+        };
+
+        let mut remaining = amount;
+
+        for grant in grants.iter_mut() {
+            if remaining <= 0.0 {
+                break;
+            }
+
+            let taken = remaining.min(grant.amount);
+            grant.amount -= taken;
+            remaining -= taken;
+
+            if grant.amount <= 0.0 {
+                grant.status = BonusGrantStatus::Expired;
+            }
+
+            result.push(grant.clone());
+        }
+
+        grants.retain(|x| x.is_open());
+
+        result
+    }
+
+    /// Closes an open grant with the status. Returns it in its closed state,
+    /// or `None` if the account has no such open grant.
+    pub fn close(
+        &mut self,
+        account_id: &str,
+        grant_id: &str,
+        status: BonusGrantStatus,
+    ) -> Option<BonusGrant> {
+        let grants = self.grants.get_mut(account_id)?;
+        let index = grants.iter().position(|x| x.id == grant_id)?;
+
+        let mut grant = grants.remove(index);
+        grant.status = status;
+
+        Some(grant)
+    }
+
+    /// Active grants that expire at or before `now`.
+    pub fn get_expired(&self, now: u64) -> Vec<BonusGrant> {
+        self.get_all(|x| x.status == BonusGrantStatus::Active && x.expires_at <= now)
+    }
+
+    /// Earned grants, still to be converted to cash.
+    pub fn get_earned(&self) -> Vec<BonusGrant> {
+        self.get_all(|x| x.status == BonusGrantStatus::Earned)
+    }
+
+    fn get_all(&self, filter: impl Fn(&BonusGrant) -> bool) -> Vec<BonusGrant> {
+        self.grants
+            .values()
+            .flatten()
+            .filter(|x| filter(x))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Into<BonusGrant> for PersistenceBonusGrantGrpcModel {
+    fn into(self) -> BonusGrant {
+        BonusGrant {
+            id: self.id,
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            amount: self.amount,
+            granted_at: self.granted_at,
+            expires_at: self.expires_at,
+            turnover_required: self.turnover_required,
+            turnover: self.turnover,
+            status: BonusGrantStatus::try_from(self.status).unwrap_or(BonusGrantStatus::Expired),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyBalance {
+    pub account_id: String,
+    pub trader_id: String,
+    pub currency: String,
+    pub balance: f64,
+    /// Cut-off the balance was taken at.
+    pub date: u64,
+}
+
+/// Cut-offs whose closing balances were captured, per account type. The
+/// balances themselves are answered from the balance ledger.
+pub struct DailyBalancesCache {
+    cutoffs: RwLock<HashMap<String, BTreeSet<u64>>>,
+}
+
+impl DailyBalancesCache {
+    pub fn new(cutoffs: HashMap<String, BTreeSet<u64>>) -> Self {
+        Self {
+            cutoffs: RwLock::new(cutoffs),
+        }
+    }
+
+    pub async fn has_snapshot(&self, account_type: &str, date: u64) -> bool {
+        let read_access = self.cutoffs.read().await;
+
+        match read_access.get(account_type) {
+            Some(cutoffs) => cutoffs.contains(&date),
+            None => false,
+        }
+    }
+
+    pub async fn add(&self, account_type: &str, date: u64) {
+        let mut write_access = self.cutoffs.write().await;
+
+        write_access
+            .entry(account_type.to_string())
+            .or_default()
+            .insert(date);
+    }
+
+    /// Captured cut-offs within `date_from..=date_to`, oldest first.
+    pub async fn get(&self, account_type: &str, date_from: u64, date_to: u64) -> Vec<u64> {
+        let read_access = self.cutoffs.read().await;
+
+        match read_access.get(account_type) {
+            Some(cutoffs) => cutoffs.range(date_from..=date_to).copied().collect(),
+            None => vec![],
+        }
+    }
+
+    /// Drops cut-offs before `before`. Returns the number of dropped
+    /// cut-offs.
+    pub async fn prune(&self, before: u64) -> usize {
+        let mut write_access = self.cutoffs.write().await;
+        let mut result = 0;
+
+        for cutoffs in write_access.values_mut() {
+            let retained = cutoffs.split_off(&before);
+            result += cutoffs.len();
+            *cutoffs = retained;
+        }
+
+        result
+    }
+}
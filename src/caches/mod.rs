@@ -1,5 +1,19 @@
+mod account_audit_cache;
+mod account_statement;
 mod accounts;
 mod accounts_cache;
+mod accounts_caches;
+mod balance_buckets;
+mod balance_ledger;
+mod bonus_grants;
+mod daily_balances_cache;
 
+pub use account_audit_cache::*;
+pub use account_statement::*;
 pub use accounts::*;
 pub use accounts_cache::*;
+pub use accounts_caches::*;
+pub use balance_buckets::*;
+pub use balance_ledger::*;
+pub use bonus_grants::*;
+pub use daily_balances_cache::*;
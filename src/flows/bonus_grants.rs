@@ -0,0 +1,153 @@
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::{
+    accounts_manager::{
+        AccountManagerUpdateAccountBalanceGrpcRequest, BonusGrantStatus, UpdateBalanceReason,
+    },
+    get_bonus_grants_event, publish_sb_events, update_balance, AccountsCache, AppContext,
+    BonusGrant, OperationContext, OperationError, OutboxEvent,
+};
+
+const BONUS_EXPIRY_CALLER: &str = "BonusExpiry";
+const BONUS_CONVERSION_CALLER: &str = "BonusConversion";
+
+/// Takes back every grant that expired unearned by `now` through a regular
+/// bonus balance update. A grant whose update fails stays active, so the
+/// next run retries it.
+pub async fn expire_bonus_grants(
+    app: &AppContext,
+    now: u64,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<(), OperationError> {
+    let mut result = Ok(());
+
+    for accounts_cache in app.accounts_caches.get_all() {
+        for grant in accounts_cache.get_expired_bonus_grants(now).await {
+            if let Err(error) = settle_bonus_grant(
+                app,
+                &accounts_cache,
+                &grant,
+                BonusGrantStatus::Expired,
+                my_telemetry,
+            )
+            .await
+            {
+                result = Err(error);
+            }
+        }
+    }
+
+    result
+}
+
+/// Moves what is left of every earned grant from the bonus balance to cash.
+/// A grant whose update fails stays earned, so the next run retries it.
+pub async fn convert_earned_bonus_grants(
+    app: &AppContext,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<(), OperationError> {
+    let mut result = Ok(());
+
+    for accounts_cache in app.accounts_caches.get_all() {
+        for grant in accounts_cache.get_earned_bonus_grants().await {
+            if let Err(error) = settle_bonus_grant(
+                app,
+                &accounts_cache,
+                &grant,
+                BonusGrantStatus::Converted,
+                my_telemetry,
+            )
+            .await
+            {
+                result = Err(error);
+            }
+        }
+    }
+
+    result
+}
+
+/// Closes the grant with the status through a balance update referencing
+/// it: an expired grant is taken off the bonus balance, a converted one is
+/// moved to cash. Trading losses may have used part of the bonus already, so
+/// never more than the bonus balance; with nothing left the grant is closed
+/// without a balance update.
+async fn settle_bonus_grant(
+    app: &AppContext,
+    accounts_cache: &AccountsCache,
+    grant: &BonusGrant,
+    status: BonusGrantStatus,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<(), OperationError> {
+    let amount = match accounts_cache
+        .get_account(&grant.trader_id, &grant.account_id)
+        .await
+    {
+        Some(account) => grant.amount.min(account.buckets.bonus),
+        None => 0.0,
+    };
+
+    if amount <= 0.0 {
+        accounts_cache
+            .close_bonus_grant(
+                &grant.trader_id,
+                &grant.account_id,
+                &grant.id,
+                status,
+                |account, grant| {
+                    vec![OutboxEvent::Persist(Box::new(get_bonus_grants_event(
+                        account,
+                        vec![grant.clone()],
+                    )))]
+                },
+            )
+            .await?;
+
+        publish_sb_events(app, my_telemetry).await;
+        return Ok(());
+    }
+
+    let (reason, delta, comment, process_id, caller) = match status {
+        BonusGrantStatus::Converted => (
+            UpdateBalanceReason::BonusConversion,
+            amount,
+            format!("Bonus {} converted to cash", grant.id),
+            format!("bonus-conversion-{}", grant.id),
+            BONUS_CONVERSION_CALLER,
+        ),
+        _ => (
+            UpdateBalanceReason::Bonus,
+            -amount,
+            format!("Bonus {} expired", grant.id),
+            format!("bonus-expiry-{}", grant.id),
+            BONUS_EXPIRY_CALLER,
+        ),
+    };
+
+    let request = AccountManagerUpdateAccountBalanceGrpcRequest {
+        trader_id: grant.trader_id.clone(),
+        account_id: grant.account_id.clone(),
+        delta,
+        comment,
+        process_id,
+        allow_negative_balance: false,
+        reason: reason as i32,
+        reference_transaction_id: Some(grant.id.clone()),
+        same_response_process_id: false,
+        account_type: accounts_cache.get_account_type().to_string(),
+        expected_version: None,
+        bonus_expires_at: None,
+        bonus_turnover_required: None,
+        trading_volume: None,
+    };
+
+    update_balance(
+        app,
+        &request,
+        &OperationContext::new(None, caller.to_string()),
+        my_telemetry,
+    )
+    .await?;
+
+    Ok(())
+}
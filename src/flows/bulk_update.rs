@@ -0,0 +1,98 @@
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::{
+    accounts_manager::{
+        AccountManagerBulkUpdateTradingDisabledGrpcRequest,
+        AccountManagerBulkUpdateTradingGroupGrpcRequest, SearchAccounts,
+    },
+    get_account_change_events, publish_account_changes, AccountChangeSbEvent, AppContext,
+    OperationContext, OperationError,
+};
+
+pub async fn bulk_update_trading_group(
+    app: &AppContext,
+    request: &AccountManagerBulkUpdateTradingGroupGrpcRequest,
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<u64, OperationError> {
+    let filter = get_filter(&request.filter)?;
+    let accounts_cache = app.accounts_caches.get(&filter.account_type)?;
+
+    if request.dry_run {
+        let accounts = accounts_cache.search(filter).await.unwrap_or_default();
+
+        return Ok(accounts
+            .iter()
+            .filter(|x| x.trading_group != request.new_trading_group)
+            .count() as u64);
+    }
+
+    let changes = accounts_cache
+        .bulk_update_trading_group(
+            filter,
+            &request.new_trading_group,
+            &request.process_id,
+            |change| {
+                let sb_event =
+                    AccountChangeSbEvent::trading_group_changed(change, &context.operation_id);
+                get_account_change_events(change, sb_event, context)
+            },
+        )
+        .await?;
+
+    publish_account_changes(app, &changes, context, my_telemetry).await;
+
+    return Ok(changes.len() as u64);
+}
+
+pub async fn bulk_update_trading_disabled(
+    app: &AppContext,
+    request: &AccountManagerBulkUpdateTradingDisabledGrpcRequest,
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<u64, OperationError> {
+    let filter = get_filter(&request.filter)?;
+    let accounts_cache = app.accounts_caches.get(&filter.account_type)?;
+
+    if request.dry_run {
+        let accounts = accounts_cache.search(filter).await.unwrap_or_default();
+
+        return Ok(accounts
+            .iter()
+            .filter(|x| x.has_trading_disabled_reason(request.reason()) != request.trading_disabled)
+            .count() as u64);
+    }
+
+    let changes = accounts_cache
+        .bulk_update_trading_disabled(
+            filter,
+            request.trading_disabled,
+            request.reason(),
+            request.comment.clone(),
+            &request.process_id,
+            |change| {
+                let sb_event = AccountChangeSbEvent::trading_disabled_changed(
+                    change,
+                    &context.operation_id,
+                    request.reason(),
+                    request.comment.clone(),
+                );
+
+                get_account_change_events(change, sb_event, context)
+            },
+        )
+        .await?;
+
+    publish_account_changes(app, &changes, context, my_telemetry).await;
+
+    return Ok(changes.len() as u64);
+}
+
+fn get_filter(filter: &Option<SearchAccounts>) -> Result<&SearchAccounts, OperationError> {
+    match filter {
+        Some(filter) => Ok(filter),
+        None => Err(OperationError::InvalidRequest(
+            "filter must be set for bulk operations".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::{
+    publish_sb_events, AppContext, DailyBalance, DailyBalancesSbEvent, OperationError, OutboxEvent,
+};
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Balances per published event, so a large account type does not go out as
+/// one oversized message.
+pub const DAILY_BALANCES_CHUNK_SIZE: usize = 1_000;
+
+/// Latest end-of-day cut-off at or before `now`.
+pub fn get_last_eod_cutoff(now: u64, cutoff: Duration) -> u64 {
+    let result = now - now % DAY_MS + cutoff.as_millis() as u64;
+
+    match result > now {
+        true => result - DAY_MS,
+        false => result,
+    }
+}
+
+/// Takes the closing balances at `date` of every account type that has no
+/// snapshot for it yet and queues them in chunks in the SB events outbox. The
+/// persistence records the cut-off once it has the last chunk, so a restart
+/// does not publish it again. A type whose balances could not be taken is
+/// left without a snapshot, so the next run retries it.
+pub async fn capture_daily_balances(
+    app: &AppContext,
+    date: u64,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<(), OperationError> {
+    let mut result = Ok(());
+
+    for accounts_cache in app.accounts_caches.get_all() {
+        let account_type = accounts_cache.get_account_type();
+
+        if app
+            .daily_balances_cache
+            .has_snapshot(account_type, date)
+            .await
+        {
+            continue;
+        }
+
+        let balances = match accounts_cache.get_balances_at(date).await {
+            Ok(balances) => balances,
+            Err(error) => {
+                result = Err(error);
+                continue;
+            }
+        };
+
+        if let Err(error) = app.sb_events_outbox.ensure_capacity() {
+            result = Err(error);
+            continue;
+        }
+
+        app.sb_events_outbox
+            .enqueue(account_type, get_daily_balances_events(date, &balances));
+        app.daily_balances_cache.add(account_type, date).await;
+    }
+
+    publish_sb_events(app, my_telemetry).await;
+
+    result
+}
+
+fn get_daily_balances_events(date: u64, balances: &[DailyBalance]) -> Vec<OutboxEvent> {
+    let chunks: Vec<&[DailyBalance]> = match balances.is_empty() {
+        true => vec![balances],
+        false => balances.chunks(DAILY_BALANCES_CHUNK_SIZE).collect(),
+    };
+
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            OutboxEvent::DailyBalances(Box::new(DailyBalancesSbEvent {
+                date_time_unix_ms: date,
+                balances: chunk.iter().cloned().map(|x| x.into()).collect(),
+                chunk: index as u32,
+                chunks_count: chunks.len() as u32,
+            }))
+        })
+        .collect()
+}
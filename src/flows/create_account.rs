@@ -0,0 +1,101 @@
+use cfd_engine_sb_contracts::AccountPersistEvent;
+use service_sdk::my_telemetry::MyTelemetryContext;
+use uuid::Uuid;
+
+use crate::{
+    accounts_manager::{AccountManagerCreateAccountGrpcRequest, AccountMetadataItemGrpcModel},
+    publish_sb_events, Account, AppContext, BalanceBuckets, BalanceLedgerEntry, OperationContext,
+    OperationError, OutboxEvent,
+};
+
+pub async fn create_account(
+    app: &AppContext,
+    request: AccountManagerCreateAccountGrpcRequest,
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<Account, OperationError> {
+    let accounts_cache = app.accounts_caches.get(&request.account_type)?;
+    let (default_account_balance, default_account_trading_group) =
+        app.settings.get_default_account_balance_and_group().await;
+
+    let tg = match request.trading_group_id {
+        Some(tg) => tg,
+        None => default_account_trading_group,
+    };
+
+    let account_to_insert = new_account(
+        request.trader_id,
+        request.currency,
+        &request.process_id,
+        default_account_balance,
+        tg,
+        request.metadata,
+    );
+
+    let account = accounts_cache
+        .add_account(account_to_insert, get_add_account_events)
+        .await?;
+
+    publish_sb_events(app, my_telemetry).await;
+    trade_log_account_added(&account, &context.operation_id, my_telemetry);
+
+    return Ok(account);
+}
+
+pub fn new_account(
+    trader_id: String,
+    currency: String,
+    process_id: &str,
+    balance: f64,
+    trading_group: String,
+    metadata: Vec<AccountMetadataItemGrpcModel>,
+) -> Account {
+    let date = chrono::offset::Utc::now().timestamp_millis() as u64;
+
+    Account {
+        id: Uuid::new_v4().to_string(),
+        balance,
+        buckets: BalanceBuckets::cash(balance),
+        currency,
+        trader_id,
+        trading_disabled: false,
+        create_date: date,
+        last_update_date: date,
+        last_update_process_id: process_id.to_string(),
+        create_process_id: process_id.to_string(),
+        trading_group,
+        metadata,
+        trading_disabled_reasons: vec![],
+        version: 1,
+        account_type: String::new(),
+    }
+}
+
+/// Persist event of an added account with its opening ledger entry.
+pub fn get_add_account_events(account: &Account) -> Vec<OutboxEvent> {
+    let sb_event = AccountPersistEvent {
+        add_account_event: Some(account.clone().into()),
+        update_account_event: None,
+        audit_records: vec![],
+        ledger_entry: Some(BalanceLedgerEntry::opening(account, account.create_date).into()),
+        bonus_grants: vec![],
+    };
+
+    vec![OutboxEvent::Persist(Box::new(sb_event))]
+}
+
+pub fn trade_log_account_added(
+    account: &Account,
+    operation_id: &str,
+    my_telemetry: &MyTelemetryContext,
+) {
+    trade_log::trade_log!(
+        &account.trader_id,
+        &account.id,
+        &account.create_process_id,
+        operation_id,
+        "Published add account event.",
+        my_telemetry.clone(),
+        "account" = account
+    );
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::{
+    accounts_manager::AccountMetadataItemGrpcModel, get_add_account_events, new_account,
+    publish_sb_events, trade_log_account_added, Account, AppContext, OperationContext,
+    OperationError,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnsureDefaultAccountsResult {
+    pub accounts: Vec<Account>,
+    pub created_accounts: Vec<Account>,
+}
+
+/// Creates the accounts from the configured templates a trader is missing. An
+/// account is considered present when the trader already has one of the
+/// account type with the same currency, whatever its trading group is now, so
+/// calling it repeatedly creates nothing new.
+/// Every created account is published as its own `add_account_event`; one the
+/// bus does not take is retried from the outbox.
+pub async fn ensure_default_accounts(
+    app: &AppContext,
+    account_type: &str,
+    trader_id: &str,
+    process_id: &str,
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<EnsureDefaultAccountsResult, OperationError> {
+    let accounts_cache = app.accounts_caches.get(account_type)?;
+    let default_accounts = app
+        .settings
+        .get_default_accounts(accounts_cache.get_account_type())
+        .await;
+    let (default_account_balance, default_account_trading_group) =
+        app.settings.get_default_account_balance_and_group().await;
+
+    let accounts_to_create = default_accounts
+        .into_iter()
+        .map(|template| {
+            new_account(
+                trader_id.to_string(),
+                template.currency,
+                process_id,
+                template.balance.unwrap_or(default_account_balance),
+                template
+                    .trading_group
+                    .unwrap_or_else(|| default_account_trading_group.clone()),
+                template
+                    .metadata
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(key, value)| AccountMetadataItemGrpcModel { key, value })
+                    .collect(),
+            )
+        })
+        .collect();
+
+    let created_accounts = accounts_cache
+        .add_missing_accounts(trader_id, accounts_to_create, get_add_account_events)
+        .await?;
+
+    publish_sb_events(app, my_telemetry).await;
+
+    for account in &created_accounts {
+        trade_log_account_added(account, &context.operation_id, my_telemetry);
+    }
+
+    let accounts = accounts_cache
+        .get_accounts(trader_id)
+        .await
+        .unwrap_or_default();
+
+    return Ok(EnsureDefaultAccountsResult {
+        accounts,
+        created_accounts,
+    });
+}
@@ -1,3 +1,21 @@
+mod bonus_grants;
+mod bulk_update;
+mod capture_daily_balances;
+mod create_account;
+mod ensure_default_accounts;
+mod operation_context;
+mod publish_change_event;
+mod publish_persist_event;
+mod update_account_settings;
 mod update_balance;
 
-pub use update_balance::*;
\ No newline at end of file
+pub use bonus_grants::*;
+pub use bulk_update::*;
+pub use capture_daily_balances::*;
+pub use create_account::*;
+pub use ensure_default_accounts::*;
+pub use operation_context::*;
+pub use publish_change_event::*;
+pub use publish_persist_event::*;
+pub use update_account_settings::*;
+pub use update_balance::*;
@@ -0,0 +1,22 @@
+use uuid::Uuid;
+
+/// Request-scoped data every mutating flow carries along for tracing.
+#[derive(Debug, Clone)]
+pub struct OperationContext {
+    /// Generated per request and returned to the caller, so one id ties the
+    /// response, the trade log lines and the published events together.
+    pub operation_id: String,
+    pub operator_id: Option<String>,
+    /// Service that sent the request.
+    pub caller: String,
+}
+
+impl OperationContext {
+    pub fn new(operator_id: Option<String>, caller: String) -> Self {
+        Self {
+            operation_id: Uuid::new_v4().to_string(),
+            operator_id,
+            caller,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::{
+    get_update_account_event, publish_sb_events, AccountAuditRecord, AccountChange,
+    AccountChangeSbEvent, AppContext, OperationContext, OutboxEvent,
+};
+
+/// Events of an account change: the account state with the audit records of
+/// the change for persistence, followed by the typed change event.
+pub fn get_account_change_events(
+    change: &AccountChange,
+    sb_event: AccountChangeSbEvent,
+    context: &OperationContext,
+) -> Vec<OutboxEvent> {
+    let audit_records = AccountAuditRecord::from_change(change, context);
+
+    vec![
+        OutboxEvent::Persist(Box::new(get_update_account_event(
+            &change.after,
+            audit_records,
+        ))),
+        OutboxEvent::Change(Box::new(sb_event)),
+    ]
+}
+
+/// Records the audit of the applied changes, whose events were queued by the
+/// cache, and publishes the queued events.
+pub async fn publish_account_changes(
+    app: &AppContext,
+    changes: &[AccountChange],
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) {
+    for change in changes {
+        app.account_audit_cache
+            .add(AccountAuditRecord::from_change(change, context))
+            .await;
+    }
+
+    publish_sb_events(app, my_telemetry).await;
+
+    for change in changes {
+        trade_log::trade_log!(
+            &change.after.trader_id,
+            &change.after.id,
+            &change.after.last_update_process_id,
+            &context.operation_id,
+            "Published account change events.",
+            my_telemetry.clone(),
+            "change" = change
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use cfd_engine_sb_contracts::{AccountBalanceUpdateSbModel, AccountPersistEvent};
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::{Account, AccountAuditRecord, AppContext, BonusGrant};
+
+/// Publishes the events queued by applied changes. Events the bus does not
+/// take stay queued and go out with the next publish or
+/// `SbEventsOutboxJob`, so the caller is not failed for them.
+pub async fn publish_sb_events(app: &AppContext, my_telemetry: &MyTelemetryContext) {
+    if let Err(err) = app.sb_events_outbox.publish(my_telemetry).await {
+        println!(
+            "{} SB events are queued for retry: {}",
+            app.sb_events_outbox.get_queued_count(),
+            err
+        );
+    }
+}
+
+/// Persist event of an account change that is not a balance operation,
+/// together with its audit records.
+pub fn get_update_account_event(
+    account: &Account,
+    audit_records: Vec<AccountAuditRecord>,
+) -> AccountPersistEvent {
+    AccountPersistEvent {
+        add_account_event: None,
+        update_account_event: Some(AccountBalanceUpdateSbModel {
+            account_after_update: Some(account.clone().into()),
+            operation: None,
+        }),
+        audit_records: audit_records.into_iter().map(|x| x.into()).collect(),
+        ledger_entry: None,
+        bonus_grants: vec![],
+    }
+}
+
+/// Persist event of bonus grants closed without a balance update. It carries
+/// the unchanged account like every other persist event.
+pub fn get_bonus_grants_event(
+    account: &Account,
+    bonus_grants: Vec<BonusGrant>,
+) -> AccountPersistEvent {
+    AccountPersistEvent {
+        add_account_event: None,
+        update_account_event: Some(AccountBalanceUpdateSbModel {
+            account_after_update: Some(account.clone().into()),
+            operation: None,
+        }),
+        audit_records: vec![],
+        ledger_entry: None,
+        bonus_grants: bonus_grants.into_iter().map(|x| x.into()).collect(),
+    }
+}
@@ -0,0 +1,84 @@
+use service_sdk::my_telemetry::MyTelemetryContext;
+
+use crate::{
+    accounts_manager::{
+        AccountManagerUpdateAccountMetadataGrpcRequest,
+        AccountManagerUpdateTradingDisabledGrpcRequest,
+        AccountManagerUpdateTradingGroupGrpcRequest,
+    },
+    get_account_change_events, publish_account_changes, AccountChange, AccountChangeSbEvent,
+    AppContext, OperationContext, OperationError,
+};
+
+pub async fn update_account_trading_disabled(
+    app: &AppContext,
+    request: &AccountManagerUpdateTradingDisabledGrpcRequest,
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<AccountChange, OperationError> {
+    let accounts_cache = app.accounts_caches.get(&request.account_type)?;
+
+    let change = accounts_cache
+        .update_trading_disabled(request, |change| {
+            let sb_event = AccountChangeSbEvent::trading_disabled_changed(
+                change,
+                &context.operation_id,
+                request.reason(),
+                request.comment.clone(),
+            );
+
+            get_account_change_events(change, sb_event, context)
+        })
+        .await?;
+
+    if change.is_changed() {
+        publish_account_changes(app, std::slice::from_ref(&change), context, my_telemetry).await;
+    }
+
+    return Ok(change);
+}
+
+pub async fn update_account_trading_group(
+    app: &AppContext,
+    request: &AccountManagerUpdateTradingGroupGrpcRequest,
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<AccountChange, OperationError> {
+    let accounts_cache = app.accounts_caches.get(&request.account_type)?;
+
+    let change = accounts_cache
+        .update_trading_group(request, |change| {
+            let sb_event =
+                AccountChangeSbEvent::trading_group_changed(change, &context.operation_id);
+            get_account_change_events(change, sb_event, context)
+        })
+        .await?;
+
+    if change.is_changed() {
+        publish_account_changes(app, std::slice::from_ref(&change), context, my_telemetry).await;
+    }
+
+    return Ok(change);
+}
+
+pub async fn update_account_metadata(
+    app: &AppContext,
+    request: &AccountManagerUpdateAccountMetadataGrpcRequest,
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) -> Result<AccountChange, OperationError> {
+    let accounts_cache = app.accounts_caches.get(&request.account_type)?;
+
+    let change = accounts_cache
+        .update_metadata(request, |change| {
+            let sb_event = AccountChangeSbEvent::metadata_changed(change, &context.operation_id);
+            get_account_change_events(change, sb_event, context)
+        })
+        .await?;
+
+    if change.is_changed() {
+        publish_account_changes(app, std::slice::from_ref(&change), context, my_telemetry).await;
+    }
+
+    return Ok(change);
+}
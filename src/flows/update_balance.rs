@@ -1,77 +1,101 @@
 use cfd_engine_sb_contracts::{
-    AccountBalanceUpdateOperationSbModel, AccountBalanceUpdateOperationType, AccountBalanceUpdateSbModel, AccountPersistEvent
+    AccountBalanceUpdateOperationSbModel, AccountBalanceUpdateOperationType,
+    AccountBalanceUpdateSbModel, AccountPersistEvent,
 };
 use service_sdk::my_telemetry::MyTelemetryContext;
 
 use crate::{
-    accounts_manager::AccountManagerUpdateAccountBalanceGrpcRequest, Account, AppContext,
-    OperationError,
+    accounts_manager::AccountManagerUpdateAccountBalanceGrpcRequest, observe_balance_update,
+    publish_sb_events, Account, AppContext, BalanceLedgerEntry, BonusGrant, OperationContext,
+    OperationError, OutboxEvent,
 };
 
 pub async fn update_balance(
     app: &AppContext,
     update_balance_request: &AccountManagerUpdateAccountBalanceGrpcRequest,
-    transaction_id: String,
-    my_telemetry: &MyTelemetryContext
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
 ) -> Result<Account, OperationError> {
-    let operation_type: AccountBalanceUpdateOperationType = update_balance_request.reason().into();
+    let accounts_cache = app
+        .accounts_caches
+        .get(&update_balance_request.account_type)?;
+
+    let balance_rules = app.settings.get_balance_rules().await;
+    let bonus_expiration = app.settings.get_bonus_expiration().await;
+    let mut sb_event = None;
 
-    let account_after_update = app
-        .accounts_cache
+    let account_after_update = accounts_cache
         .update_balance(
-            &update_balance_request.trader_id,
-            &update_balance_request.account_id,
-            update_balance_request.delta,
-            &update_balance_request.process_id,
-            update_balance_request.allow_negative_balance,
+            update_balance_request,
+            &balance_rules,
+            bonus_expiration,
+            &context.operation_id,
+            |account, ledger_entry, bonus_grants| {
+                let event = get_balance_update_event(
+                    update_balance_request,
+                    &context.operation_id,
+                    account,
+                    ledger_entry,
+                    bonus_grants,
+                );
+                sb_event = Some(event.clone());
+
+                vec![OutboxEvent::Persist(Box::new(event))]
+            },
         )
         .await?;
 
+    observe_balance_update(
+        update_balance_request.reason(),
+        &account_after_update.currency,
+        update_balance_request.delta,
+    );
+
+    publish_sb_events(app, my_telemetry).await;
+
+    trade_log::trade_log!(
+        &update_balance_request.trader_id,
+        &update_balance_request.account_id,
+        &update_balance_request.process_id,
+        &context.operation_id,
+        "Success update balance operation.",
+        my_telemetry.clone(),
+        "request" = &update_balance_request,
+        "sb_event" = &sb_event
+    );
+
+    return Ok(account_after_update);
+}
+
+fn get_balance_update_event(
+    update_balance_request: &AccountManagerUpdateAccountBalanceGrpcRequest,
+    operation_id: &str,
+    account_after_update: &Account,
+    ledger_entry: &BalanceLedgerEntry,
+    bonus_grants: &[BonusGrant],
+) -> AccountPersistEvent {
+    let operation_type: AccountBalanceUpdateOperationType = update_balance_request.reason().into();
+
     let balance_update_sb_operation = AccountBalanceUpdateOperationSbModel {
-        id: transaction_id.to_string(),
+        id: operation_id.to_string(),
         trader_id: update_balance_request.trader_id.clone(),
         account_id: update_balance_request.account_id.clone(),
         operation_type: operation_type as i32,
         process_id: Some(update_balance_request.process_id.clone()),
         delta: update_balance_request.delta,
-        date_time_unix_ms: chrono::offset::Utc::now().timestamp_millis() as u64,
+        date_time_unix_ms: ledger_entry.date,
         comment: Some(update_balance_request.comment.clone()),
         reference_operation_id: update_balance_request.reference_transaction_id.clone(),
     };
 
-    let sb_event = AccountPersistEvent{
+    AccountPersistEvent {
         add_account_event: None,
-        update_account_event:Some(AccountBalanceUpdateSbModel{
+        update_account_event: Some(AccountBalanceUpdateSbModel {
             account_after_update: Some(account_after_update.clone().into()),
             operation: Some(balance_update_sb_operation),
         }),
-    };
-
-    app.account_persist_events_publisher
-        .publish_with_headers(
-            &sb_event,
-            vec![(
-                "type".to_string(),
-                app.settings_reader.get_env_type().await,
-            )]
-            .into(),
-            Some(my_telemetry),
-        )
-        .await
-        .unwrap();
-
-
-    trade_log::trade_log!(
-        &update_balance_request.trader_id,
-        &update_balance_request.account_id,
-        &update_balance_request.process_id,
-        &transaction_id,
-        "Success update balance operation.",
-        my_telemetry.clone(),
-        "request" = &update_balance_request,
-        "sb_event" = &sb_event
-    );
-
-
-    return Ok(account_after_update);
+        audit_records: vec![],
+        ledger_entry: Some(ledger_entry.clone().into()),
+        bonus_grants: bonus_grants.iter().cloned().map(|x| x.into()).collect(),
+    }
 }
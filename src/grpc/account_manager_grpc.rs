@@ -1,11 +1,33 @@
 use std::{pin::Pin, vec};
 
 use crate::accounts_manager::{
-    AccountManagerGetAccountsByGroupGrpcRequest, AccountManagerGetTraderIdByAccountIdGrpcRequest,
-    AccountManagerGetTraderIdByAccountIdGrpcResponse, AccountManagerUpdateTradingGroupGrpcRequest,
-    AccountsManagerOperationResult, SearchAccounts,
+    AccountAuditGrpcModel, AccountDailyBalanceGrpcModel, AccountManagerBulkUpdateGrpcResponse,
+    AccountManagerBulkUpdateTradingDisabledGrpcRequest,
+    AccountManagerBulkUpdateTradingGroupGrpcRequest,
+    AccountManagerEnsureDefaultAccountsGrpcRequest,
+    AccountManagerEnsureDefaultAccountsGrpcResponse, AccountManagerExportStatementGrpcRequest,
+    AccountManagerExportStatementGrpcResponse, AccountManagerGetAccountAuditGrpcRequest,
+    AccountManagerGetAccountsByGroupGrpcRequest, AccountManagerGetActiveBonusesGrpcRequest,
+    AccountManagerGetBalanceAtGrpcRequest, AccountManagerGetBalanceAtGrpcResponse,
+    AccountManagerGetDailyBalancesGrpcRequest, AccountManagerGetStatementGrpcRequest,
+    AccountManagerGetTraderIdByAccountIdGrpcRequest,
+    AccountManagerGetTraderIdByAccountIdGrpcResponse, AccountManagerReplayLedgerGrpcRequest,
+    AccountManagerReplayLedgerGrpcResponse, AccountManagerSubscribeAccountUpdatesGrpcRequest,
+    AccountManagerUpdateAccountMetadataGrpcRequest,
+    AccountManagerUpdateAccountMetadataGrpcResponse, AccountManagerUpdateTradingGroupGrpcRequest,
+    AccountManagerUpdateTradingGroupGrpcResponse, AccountStatementFormat,
+    AccountStatementRowGrpcModel, BonusGrantGrpcModel, SearchAccounts,
+};
+use crate::{
+    bulk_update_trading_disabled, bulk_update_trading_group, create_account,
+    ensure_default_accounts, update_account_metadata, update_account_trading_disabled,
+    update_account_trading_group, update_balance, OperationContext, OperationError,
+};
+
+use super::{
+    get_operation_context, subscribe_account_updates, track_rpc, ValidateRequest,
+    OPERATION_ID_HEADER,
 };
-use crate::update_balance;
 use crate::{
     accounts_manager::{
         accounts_manager_grpc_service_server::AccountsManagerGrpcService, AccountGrpcModel,
@@ -18,10 +40,10 @@ use crate::{
     },
     Account,
 };
-use cfd_engine_sb_contracts::{AccountBalanceUpdateSbModel, AccountPersistEvent};
 use service_sdk::my_grpc_extensions::prelude::Stream;
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
-use uuid::Uuid;
 
 use super::server::GrpcService;
 use service_sdk::my_grpc_extensions;
@@ -41,58 +63,53 @@ impl AccountsManagerGrpcService for GrpcService {
         &self,
         request: tonic::Request<AccountManagerCreateAccountGrpcRequest>,
     ) -> Result<tonic::Response<AccountGrpcModel>, tonic::Status> {
-        let request = request.into_inner();
+        track_rpc("CreateAccount", async {
+            let caller = self
+                .authorize(
+                    &request,
+                    "CreateAccount",
+                    Some(&request.get_ref().trader_id),
+                    &my_telemetry,
+                )
+                .await?;
 
-        let (default_account_balance, default_account_trading_group) = self
-            .app
-            .settings_reader
-            .get_default_account_balance_and_group()
-            .await;
+            let context = get_operation_context(&request, &caller);
+            let request = request.into_inner();
 
-        let tg = match request.trading_group_id {
-            Some(tg) => tg,
-            None => default_account_trading_group,
-        };
-
-        let date = chrono::offset::Utc::now().timestamp_millis() as u64;
-        let account_to_insert = Account {
-            id: Uuid::new_v4().to_string(),
-            balance: default_account_balance,
-            currency: request.currency,
-            trader_id: request.trader_id,
-            trading_disabled: false,
-            create_date: date,
-            last_update_date: date,
-            last_update_process_id: request.process_id.clone(),
-            create_process_id: request.process_id.clone(),
-            trading_group: tg,
-            metadata: request.metadata,
-        };
-
-        let account = self
-            .app
-            .accounts_cache
-            .add_account(account_to_insert.clone())
-            .await;
+            trade_log_request_received(
+                &request.trader_id,
+                "",
+                &request.process_id,
+                "Got create account request.",
+                &request,
+                &context,
+                &my_telemetry,
+            );
 
-        self.app
-            .account_persist_events_publisher
-            .publish_with_headers(
-                &AccountPersistEvent {
-                    add_account_event: Some(account.clone().into()),
-                    update_account_event: None,
-                },
-                vec![(
-                    "type".to_string(),
-                    self.app.settings_reader.get_env_type().await,
-                )]
-                .into(),
-                Some(my_telemetry),
-            )
-            .await
-            .unwrap();
+            let result = match request.validate() {
+                Ok(_) => create_account(&self.app, request.clone(), &context, &my_telemetry).await,
+                Err(error) => Err(error),
+            };
+
+            trade_log::trade_log!(
+                &request.trader_id,
+                "",
+                &request.process_id,
+                &context.operation_id,
+                "Executed create account request.",
+                my_telemetry.clone(),
+                "request" = &request,
+                "result" = &result
+            );
+
+            let account = result.map_err(|error| get_error_status(error, &context))?;
+
+            let mut response: AccountGrpcModel = account.into();
+            response.operation_id = Some(context.operation_id);
 
-        return Ok(tonic::Response::new(account_to_insert.into()));
+            return Ok(tonic::Response::new(response));
+        })
+        .await
     }
 
     #[with_telemetry]
@@ -100,19 +117,30 @@ impl AccountsManagerGrpcService for GrpcService {
         &self,
         request: tonic::Request<AccountManagerGetClientAccountGrpcRequest>,
     ) -> Result<tonic::Response<AccountManagerGetClientAccountGrpcResponse>, tonic::Status> {
-        let request = request.into_inner();
-        let AccountManagerGetClientAccountGrpcRequest {
-            trader_id,
-            account_id,
-        } = request;
-
-        let account = self
-            .app
-            .accounts_cache
-            .get_account(&trader_id, &account_id)
-            .await;
+        track_rpc("GetClientAccount", async {
+            self.authorize(
+                &request,
+                "GetClientAccount",
+                Some(&request.get_ref().trader_id),
+                &my_telemetry,
+            )
+            .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let AccountManagerGetClientAccountGrpcRequest {
+                trader_id,
+                account_id,
+                account_type,
+            } = request;
 
-        return Ok(tonic::Response::new(account.into()));
+            let accounts_cache = self.app.accounts_caches.get(&account_type)?;
+            let account = accounts_cache.get_account(&trader_id, &account_id).await;
+
+            return Ok(tonic::Response::new(account.into()));
+        })
+        .await
     }
 
     #[with_telemetry]
@@ -120,54 +148,32 @@ impl AccountsManagerGrpcService for GrpcService {
         &self,
         request: tonic::Request<AccountManagerGetClientAccountsGrpcRequest>,
     ) -> Result<tonic::Response<Self::GetClientAccountsStream>, tonic::Status> {
-        let request = request.into_inner();
-        let AccountManagerGetClientAccountsGrpcRequest { trader_id } = request;
-        let accounts = self
-            .app
-            .accounts_cache
-            .get_accounts(&trader_id)
-            .await
-            .map(|x| {
-                x.into_iter()
-                    .map(|acc| acc.into())
-                    .collect::<Vec<AccountGrpcModel>>()
-            });
-
-        let accounts_to_send = match accounts {
-            Some(accounts) => accounts,
-            None => {
-                if let Some(def_currency) = self
-                    .app
-                    .settings_reader
-                    .get_accounts_default_currency()
-                    .await
-                {
-                    let request = AccountManagerCreateAccountGrpcRequest {
-                        trader_id: trader_id.clone(),
-                        currency: def_currency.clone(),
-                        process_id: Uuid::new_v4().to_string(),
-                        trading_group_id: None,
-                        metadata: vec![],
-                    };
-
-                    let account = self
-                        .create_account(Request::new(request))
-                        .await
-                        .unwrap()
-                        .into_inner();
+        track_rpc("GetClientAccounts", async {
+            self.authorize(
+                &request,
+                "GetClientAccounts",
+                Some(&request.get_ref().trader_id),
+                &my_telemetry,
+            )
+            .await?;
 
-                    vec![account]
-                } else {
-                    vec![]
-                }
-            }
-        };
+            let request = request.into_inner();
+            request.validate()?;
 
-        return service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
-            accounts_to_send.into_iter(),
-            |x| x,
-        )
-        .await;
+            let AccountManagerGetClientAccountsGrpcRequest {
+                trader_id,
+                account_type,
+            } = request;
+            let accounts_cache = self.app.accounts_caches.get(&account_type)?;
+            let accounts = accounts_cache.get_accounts(&trader_id).await;
+
+            return service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                get_accounts_vector(accounts).into_iter(),
+                |x| x,
+            )
+            .await;
+        })
+        .await
     }
 
     #[with_telemetry]
@@ -175,23 +181,105 @@ impl AccountsManagerGrpcService for GrpcService {
         &self,
         request: tonic::Request<AccountManagerGetAccountsByGroupGrpcRequest>,
     ) -> Result<tonic::Response<Self::GetTradingGroupAccountsStream>, tonic::Status> {
-        let request = request.into_inner();
-        let AccountManagerGetAccountsByGroupGrpcRequest { trading_group } = request;
-        let accounts = self
-            .app
-            .accounts_cache
-            .get_accounts_by_trading_group(&trading_group)
-            .await;
+        track_rpc("GetTradingGroupAccounts", async {
+            self.authorize(&request, "GetTradingGroupAccounts", None, &my_telemetry)
+                .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let AccountManagerGetAccountsByGroupGrpcRequest {
+                trading_group,
+                account_type,
+            } = request;
+            let accounts_cache = self.app.accounts_caches.get(&account_type)?;
+            let accounts = accounts_cache
+                .get_accounts_by_trading_group(&trading_group)
+                .await;
 
-        let accounts = match accounts {
-            Some(accounts) => accounts,
-            None => vec![],
-        };
+            let accounts = match accounts {
+                Some(accounts) => accounts,
+                None => vec![],
+            };
 
-        service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
-            accounts.into_iter(),
-            |x| x.into(),
-        )
+            service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                accounts.into_iter(),
+                |x| x.into(),
+            )
+            .await
+        })
+        .await
+    }
+
+    #[with_telemetry]
+    async fn ensure_default_accounts(
+        &self,
+        request: tonic::Request<AccountManagerEnsureDefaultAccountsGrpcRequest>,
+    ) -> Result<tonic::Response<AccountManagerEnsureDefaultAccountsGrpcResponse>, tonic::Status>
+    {
+        track_rpc("EnsureDefaultAccounts", async {
+            let caller = self
+                .authorize(
+                    &request,
+                    "EnsureDefaultAccounts",
+                    Some(&request.get_ref().trader_id),
+                    &my_telemetry,
+                )
+                .await?;
+
+            let context = get_operation_context(&request, &caller);
+            let request = request.into_inner();
+
+            trade_log_request_received(
+                &request.trader_id,
+                "",
+                &request.process_id,
+                "Got ensure default accounts request.",
+                &request,
+                &context,
+                &my_telemetry,
+            );
+
+            let result = match request.validate() {
+                Ok(_) => {
+                    ensure_default_accounts(
+                        &self.app,
+                        &request.account_type,
+                        &request.trader_id,
+                        &request.process_id,
+                        &context,
+                        &my_telemetry,
+                    )
+                    .await
+                }
+                Err(error) => Err(error),
+            };
+
+            trade_log::trade_log!(
+                &request.trader_id,
+                "",
+                &request.process_id,
+                &context.operation_id,
+                "Executed ensure default accounts request.",
+                my_telemetry.clone(),
+                "request" = &request,
+                "result" = &result
+            );
+
+            let result = result.map_err(|error| get_error_status(error, &context))?;
+
+            Ok(tonic::Response::new(
+                AccountManagerEnsureDefaultAccountsGrpcResponse {
+                    created_account_ids: result
+                        .created_accounts
+                        .into_iter()
+                        .map(|x| x.id)
+                        .collect(),
+                    accounts: get_accounts_vector(Some(result.accounts)),
+                    operation_id: context.operation_id,
+                },
+            ))
+        })
         .await
     }
 
@@ -201,73 +289,129 @@ impl AccountsManagerGrpcService for GrpcService {
         request: tonic::Request<AccountManagerUpdateAccountBalanceGrpcRequest>,
     ) -> Result<tonic::Response<AccountManagerUpdateAccountBalanceGrpcResponse>, tonic::Status>
     {
-        let request = request.into_inner();
-        let transaction_id = Uuid::new_v4().to_string();
-
-        trade_log::trade_log!(
-            &request.trader_id,
-            &request.account_id,
-            &request.process_id,
-            &transaction_id,
-            "Got update balance request.",
-            my_telemetry.clone(),
-            "request" = &request
-        );
-
-        if let Some(response) = self.app.cache.get(&request.process_id).await {
+        track_rpc("UpdateClientAccountBalance", async {
+            let caller = self
+                .authorize(
+                    &request,
+                    "UpdateClientAccountBalance",
+                    Some(&request.get_ref().trader_id),
+                    &my_telemetry,
+                )
+                .await?;
+
+            let context = get_operation_context(&request, &caller);
+            let request = request.into_inner();
+            let transaction_id = context.operation_id.clone();
+
+            trade_log_request_received(
+                &request.trader_id,
+                &request.account_id,
+                &request.process_id,
+                "Got update balance request.",
+                &request,
+                &context,
+                &my_telemetry,
+            );
+
+            if let Err(error) = request.validate() {
+                trade_log::trade_log!(
+                    &request.trader_id,
+                    &request.account_id,
+                    &request.process_id,
+                    &transaction_id,
+                    "Update balance request is invalid.",
+                    my_telemetry.clone(),
+                    "request" = &request,
+                    "error" = &error
+                );
+
+                return Ok(tonic::Response::new(balance_error_response(
+                    error,
+                    &transaction_id,
+                )));
+            }
+
+            if let Err(status) = caller.ensure_balance_reason_allowed(request.reason()) {
+                trade_log::trade_log!(
+                    &request.trader_id,
+                    &request.account_id,
+                    &request.process_id,
+                    &transaction_id,
+                    "Update balance reason is not allowed for caller.",
+                    my_telemetry.clone(),
+                    "request" = &request,
+                    "caller" = &caller.name
+                );
+
+                return Err(status);
+            }
+
+            let process_key = match self.app.accounts_caches.get(&request.account_type) {
+                Ok(accounts_cache) => {
+                    format!(
+                        "{}:{}",
+                        accounts_cache.get_account_type(),
+                        request.process_id
+                    )
+                }
+                Err(error) => {
+                    return Ok(tonic::Response::new(balance_error_response(
+                        error,
+                        &transaction_id,
+                    )))
+                }
+            };
+
+            if let Some(response) = self.app.cache.get(&process_key).await {
+                trade_log::trade_log!(
+                    &request.trader_id,
+                    &request.account_id,
+                    &request.process_id,
+                    &transaction_id,
+                    "Found request with same process id - returning error.",
+                    my_telemetry.clone(),
+                    "request" = &request,
+                    "previous_response" = &response
+                );
+
+                return Ok(tonic::Response::new(balance_error_response(
+                    OperationError::ProcessIdDuplicate,
+                    &transaction_id,
+                )));
+            }
+
+            let update_balance_result =
+                update_balance(&self.app, &request, &context, &my_telemetry).await;
+
             trade_log::trade_log!(
                 &request.trader_id,
                 &request.account_id,
                 &request.process_id,
                 &transaction_id,
-                "Found request with same process id - returning error.",
+                "Executed update balance request.",
                 my_telemetry.clone(),
                 "request" = &request,
-                "previous_response" = &response
+                "result" = &update_balance_result
             );
 
-            return Ok(tonic::Response::new(
-                AccountManagerUpdateAccountBalanceGrpcResponse {
-                    result: AccountsManagerOperationResult::ProcessIdDuplicate as i32,
-                    update_balance_info: None,
-                },
-            ));
-        }
-
-        let update_balance_result =
-            update_balance(&self.app, &request, transaction_id.clone(), &my_telemetry).await;
-
-        trade_log::trade_log!(
-            &request.trader_id,
-            &request.account_id,
-            &request.process_id,
-            &transaction_id,
-            "Executed update balance request.",
-            my_telemetry.clone(),
-            "request" = &request,
-            "result" = &update_balance_result
-        );
-
-        let response = match update_balance_result {
-            Ok(account) => AccountManagerUpdateAccountBalanceGrpcResponse {
-                result: 0,
-                update_balance_info: Some(AccountManagerUpdateBalanceBalanceGrpcInfo {
-                    account: Some(account.into()),
+            let response = match update_balance_result {
+                Ok(account) => AccountManagerUpdateAccountBalanceGrpcResponse {
+                    result: 0,
+                    update_balance_info: Some(AccountManagerUpdateBalanceBalanceGrpcInfo {
+                        account: Some(account.into()),
+                        operation_id: transaction_id.clone(),
+                    }),
+                    error_message: None,
                     operation_id: transaction_id.clone(),
-                }),
-            },
-            Err(error) => AccountManagerUpdateAccountBalanceGrpcResponse {
-                result: error.as_grpc_error(),
-                update_balance_info: None,
-            },
-        };
-
-        self.app
-            .cache
-            .set(&request.process_id, response.clone())
-            .await;
+                },
+                Err(error) => balance_error_response(error, &transaction_id),
+            };
+
+            self.app.cache.set(&process_key, response.clone()).await;
 
-        Ok(tonic::Response::new(response))
+            Ok(tonic::Response::new(response))
+        })
+        .await
     }
 
     #[with_telemetry]
@@ -276,125 +420,290 @@ impl AccountsManagerGrpcService for GrpcService {
         request: tonic::Request<AccountManagerUpdateTradingDisabledGrpcRequest>,
     ) -> Result<tonic::Response<AccountManagerUpdateTradingDisabledGrpcResponse>, tonic::Status>
     {
-        let request = request.into_inner();
+        track_rpc("UpdateAccountTradingDisabled", async {
+            let caller = self
+                .authorize(
+                    &request,
+                    "UpdateAccountTradingDisabled",
+                    Some(&request.get_ref().trader_id),
+                    &my_telemetry,
+                )
+                .await?;
+
+            let context = get_operation_context(&request, &caller);
+            let request = request.into_inner();
 
-        let update_balance_result = self
-            .app
-            .accounts_cache
-            .update_trading_disabled(
+            trade_log_request_received(
                 &request.trader_id,
                 &request.account_id,
-                request.trading_disabled,
                 &request.process_id,
-            )
-            .await;
+                "Got update trading disabled request.",
+                &request,
+                &context,
+                &my_telemetry,
+            );
 
-        trade_log::trade_log!(
-            &request.trader_id,
-            &request.account_id,
-            &request.process_id,
-            "",
-            "Got update trading disabled request.",
-            my_telemetry.clone(),
-            "request" = &request
-        );
-        let response = match update_balance_result {
-            Ok(account) => {
-                self.app
-                    .account_persist_events_publisher
-                    .publish_with_headers(
-                        &AccountPersistEvent {
-                            add_account_event: None,
-                            update_account_event: Some(AccountBalanceUpdateSbModel {
-                                account_after_update: Some(account.clone().into()),
-                                operation: None,
-                            }),
-                        },
-                        vec![(
-                            "type".to_string(),
-                            self.app.settings_reader.get_env_type().await,
-                        )]
-                        .into(),
-                        Some(my_telemetry),
-                    )
-                    .await
-                    .unwrap();
-                AccountManagerUpdateTradingDisabledGrpcResponse {
-                    result: 0,
-                    account: Some(account.into()),
+            let update_result = match request.validate() {
+                Ok(_) => {
+                    update_account_trading_disabled(&self.app, &request, &context, &my_telemetry)
+                        .await
                 }
-            }
-            Err(error) => AccountManagerUpdateTradingDisabledGrpcResponse {
-                result: error.as_grpc_error(),
-                account: None,
-            },
-        };
+                Err(error) => Err(error),
+            };
+
+            trade_log::trade_log!(
+                &request.trader_id,
+                &request.account_id,
+                &request.process_id,
+                &context.operation_id,
+                "Executed update trading disabled request.",
+                my_telemetry.clone(),
+                "request" = &request,
+                "result" = &update_result
+            );
+
+            let mut response = match update_result {
+                Ok(change) => AccountManagerUpdateTradingDisabledGrpcResponse {
+                    result: 0,
+                    account: Some(change.after.into()),
+                    error_message: None,
+                    previous_trading_disabled: Some(change.before.trading_disabled),
+                    operation_id: String::new(),
+                },
+                Err(error) => error.into(),
+            };
+
+            response.operation_id = context.operation_id;
 
-        Ok(tonic::Response::new(response))
+            Ok(tonic::Response::new(response))
+        })
+        .await
     }
 
     #[with_telemetry]
     async fn update_account_trading_group(
         &self,
         request: tonic::Request<AccountManagerUpdateTradingGroupGrpcRequest>,
-    ) -> Result<tonic::Response<AccountManagerUpdateTradingDisabledGrpcResponse>, tonic::Status>
-    {
-        let request = request.into_inner();
+    ) -> Result<tonic::Response<AccountManagerUpdateTradingGroupGrpcResponse>, tonic::Status> {
+        track_rpc("UpdateAccountTradingGroup", async {
+            let caller = self
+                .authorize(
+                    &request,
+                    "UpdateAccountTradingGroup",
+                    Some(&request.get_ref().trader_id),
+                    &my_telemetry,
+                )
+                .await?;
 
-        let update_balance_result = self
-            .app
-            .accounts_cache
-            .update_trading_group(
+            let context = get_operation_context(&request, &caller);
+            let request = request.into_inner();
+
+            trade_log_request_received(
                 &request.trader_id,
                 &request.account_id,
-                request.new_trading_group.as_str(),
                 &request.process_id,
-            )
-            .await;
+                "Got update trading group request.",
+                &request,
+                &context,
+                &my_telemetry,
+            );
 
-        trade_log::trade_log!(
-            &request.trader_id,
-            &request.account_id,
-            &request.process_id,
-            "",
-            "Got update trading group request.",
-            my_telemetry.clone(),
-            "request" = &request
-        );
-
-        let response = match update_balance_result {
-            Ok(account) => {
-                self.app
-                    .account_persist_events_publisher
-                    .publish_with_headers(
-                        &AccountPersistEvent {
-                            add_account_event: None,
-                            update_account_event: Some(AccountBalanceUpdateSbModel {
-                                account_after_update: Some(account.clone().into()),
-                                operation: None,
-                            }),
-                        },
-                        vec![(
-                            "type".to_string(),
-                            self.app.settings_reader.get_env_type().await,
-                        )]
-                        .into(),
-                        Some(my_telemetry),
-                    )
-                    .await
-                    .unwrap();
-                AccountManagerUpdateTradingDisabledGrpcResponse {
+            let update_result = match request.validate() {
+                Ok(_) => {
+                    update_account_trading_group(&self.app, &request, &context, &my_telemetry).await
+                }
+                Err(error) => Err(error),
+            };
+
+            trade_log::trade_log!(
+                &request.trader_id,
+                &request.account_id,
+                &request.process_id,
+                &context.operation_id,
+                "Executed update trading group request.",
+                my_telemetry.clone(),
+                "request" = &request,
+                "result" = &update_result
+            );
+
+            let mut response = match update_result {
+                Ok(change) => AccountManagerUpdateTradingGroupGrpcResponse {
                     result: 0,
-                    account: Some(account.into()),
+                    account: Some(change.after.into()),
+                    error_message: None,
+                    previous_trading_group: Some(change.before.trading_group),
+                    operation_id: String::new(),
+                },
+                Err(error) => error.into(),
+            };
+
+            response.operation_id = context.operation_id;
+
+            Ok(tonic::Response::new(response))
+        })
+        .await
+    }
+
+    #[with_telemetry]
+    async fn update_account_metadata(
+        &self,
+        request: tonic::Request<AccountManagerUpdateAccountMetadataGrpcRequest>,
+    ) -> Result<tonic::Response<AccountManagerUpdateAccountMetadataGrpcResponse>, tonic::Status>
+    {
+        track_rpc("UpdateAccountMetadata", async {
+            let caller = self
+                .authorize(
+                    &request,
+                    "UpdateAccountMetadata",
+                    Some(&request.get_ref().trader_id),
+                    &my_telemetry,
+                )
+                .await?;
+
+            let context = get_operation_context(&request, &caller);
+            let request = request.into_inner();
+
+            trade_log_request_received(
+                &request.trader_id,
+                &request.account_id,
+                &request.process_id,
+                "Got update metadata request.",
+                &request,
+                &context,
+                &my_telemetry,
+            );
+
+            let update_result = match request.validate() {
+                Ok(_) => {
+                    update_account_metadata(&self.app, &request, &context, &my_telemetry).await
                 }
-            }
-            Err(error) => AccountManagerUpdateTradingDisabledGrpcResponse {
-                result: error.as_grpc_error(),
-                account: None,
-            },
-        };
+                Err(error) => Err(error),
+            };
+
+            trade_log::trade_log!(
+                &request.trader_id,
+                &request.account_id,
+                &request.process_id,
+                &context.operation_id,
+                "Executed update metadata request.",
+                my_telemetry.clone(),
+                "request" = &request,
+                "result" = &update_result
+            );
+
+            let change = update_result.map_err(|error| get_error_status(error, &context))?;
 
-        Ok(tonic::Response::new(response))
+            Ok(tonic::Response::new(
+                AccountManagerUpdateAccountMetadataGrpcResponse {
+                    account: Some(change.after.into()),
+                    previous_metadata: change.before.metadata,
+                    operation_id: context.operation_id,
+                },
+            ))
+        })
+        .await
+    }
+
+    #[with_telemetry]
+    async fn bulk_update_trading_group(
+        &self,
+        request: tonic::Request<AccountManagerBulkUpdateTradingGroupGrpcRequest>,
+    ) -> Result<tonic::Response<AccountManagerBulkUpdateGrpcResponse>, tonic::Status> {
+        track_rpc("BulkUpdateTradingGroup", async {
+            let caller = self
+                .authorize(&request, "BulkUpdateTradingGroup", None, &my_telemetry)
+                .await?;
+
+            let context = get_operation_context(&request, &caller);
+            let request = request.into_inner();
+
+            trade_log_request_received(
+                "",
+                "",
+                &request.process_id,
+                "Got bulk update trading group request.",
+                &request,
+                &context,
+                &my_telemetry,
+            );
+
+            let result = match request.validate() {
+                Ok(_) => {
+                    bulk_update_trading_group(&self.app, &request, &context, &my_telemetry).await
+                }
+                Err(error) => Err(error),
+            };
+
+            trade_log::trade_log!(
+                "",
+                "",
+                &request.process_id,
+                &context.operation_id,
+                "Executed bulk update trading group request.",
+                my_telemetry.clone(),
+                "request" = &request,
+                "result" = &result
+            );
+
+            let affected_count = result.map_err(|error| get_error_status(error, &context))?;
+
+            Ok(tonic::Response::new(AccountManagerBulkUpdateGrpcResponse {
+                affected_count,
+                operation_id: context.operation_id,
+            }))
+        })
+        .await
+    }
+
+    #[with_telemetry]
+    async fn bulk_update_trading_disabled(
+        &self,
+        request: tonic::Request<AccountManagerBulkUpdateTradingDisabledGrpcRequest>,
+    ) -> Result<tonic::Response<AccountManagerBulkUpdateGrpcResponse>, tonic::Status> {
+        track_rpc("BulkUpdateTradingDisabled", async {
+            let caller = self
+                .authorize(&request, "BulkUpdateTradingDisabled", None, &my_telemetry)
+                .await?;
+
+            let context = get_operation_context(&request, &caller);
+            let request = request.into_inner();
+
+            trade_log_request_received(
+                "",
+                "",
+                &request.process_id,
+                "Got bulk update trading disabled request.",
+                &request,
+                &context,
+                &my_telemetry,
+            );
+
+            let result = match request.validate() {
+                Ok(_) => {
+                    bulk_update_trading_disabled(&self.app, &request, &context, &my_telemetry).await
+                }
+                Err(error) => Err(error),
+            };
+
+            trade_log::trade_log!(
+                "",
+                "",
+                &request.process_id,
+                &context.operation_id,
+                "Executed bulk update trading disabled request.",
+                my_telemetry.clone(),
+                "request" = &request,
+                "result" = &result
+            );
+
+            let affected_count = result.map_err(|error| get_error_status(error, &context))?;
+
+            Ok(tonic::Response::new(AccountManagerBulkUpdateGrpcResponse {
+                affected_count,
+                operation_id: context.operation_id,
+            }))
+        })
+        .await
     }
 
     #[with_telemetry]
@@ -402,17 +711,24 @@ impl AccountsManagerGrpcService for GrpcService {
         &self,
         request: Request<AccountManagerGetTraderIdByAccountIdGrpcRequest>,
     ) -> Result<Response<AccountManagerGetTraderIdByAccountIdGrpcResponse>, Status> {
-        let request = request.into_inner();
-        let account_id = request.account_id;
+        track_rpc("GetTraderIdByAccountId", async {
+            self.authorize(&request, "GetTraderIdByAccountId", None, &my_telemetry)
+                .await?;
 
-        let result = self
-            .app
-            .accounts_cache
-            .get_trader_id_by_account_id(account_id.as_str())
-            .await;
-        Ok(Response::new(
-            AccountManagerGetTraderIdByAccountIdGrpcResponse { trader_id: result },
-        ))
+            let request = request.into_inner();
+            request.validate()?;
+
+            let account_id = request.account_id;
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+
+            let result = accounts_cache
+                .get_trader_id_by_account_id(account_id.as_str())
+                .await;
+            Ok(Response::new(
+                AccountManagerGetTraderIdByAccountIdGrpcResponse { trader_id: result },
+            ))
+        })
+        .await
     }
 
     type SearchStream = Pin<
@@ -424,20 +740,401 @@ impl AccountsManagerGrpcService for GrpcService {
         &self,
         request: Request<SearchAccounts>,
     ) -> Result<Response<Self::SearchStream>, Status> {
-        let request = request.into_inner();
-        let result = self.app.accounts_cache.search(&request).await;
-        let accounts = get_accounts_vector(result);
-        service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
-            accounts.into_iter(),
-            |x| x,
-        )
+        track_rpc("Search", async {
+            self.authorize(&request, "Search", None, &my_telemetry)
+                .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+            let result = accounts_cache.search(&request).await;
+            let accounts = get_accounts_vector(result);
+            service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                accounts.into_iter(),
+                |x| x,
+            )
+            .await
+        })
+        .await
+    }
+
+    type SubscribeAccountUpdatesStream = Pin<
+        Box<dyn Stream<Item = Result<AccountGrpcModel, tonic::Status>> + Send + Sync + 'static>,
+    >;
+
+    #[with_telemetry]
+    async fn subscribe_account_updates(
+        &self,
+        request: Request<AccountManagerSubscribeAccountUpdatesGrpcRequest>,
+    ) -> Result<Response<Self::SubscribeAccountUpdatesStream>, Status> {
+        track_rpc("SubscribeAccountUpdates", async {
+            self.authorize(&request, "SubscribeAccountUpdates", None, &my_telemetry)
+                .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+            let receiver =
+                subscribe_account_updates(accounts_cache, request.into(), my_telemetry.clone());
+            let stream: Self::SubscribeAccountUpdatesStream =
+                Box::pin(ReceiverStream::new(receiver));
+
+            Ok(Response::new(stream))
+        })
+        .await
+    }
+
+    type GetAccountAuditStream = Pin<
+        Box<
+            dyn Stream<Item = Result<AccountAuditGrpcModel, tonic::Status>> + Send + Sync + 'static,
+        >,
+    >;
+
+    #[with_telemetry]
+    async fn get_account_audit(
+        &self,
+        request: Request<AccountManagerGetAccountAuditGrpcRequest>,
+    ) -> Result<Response<Self::GetAccountAuditStream>, Status> {
+        track_rpc("GetAccountAudit", async {
+            self.authorize(
+                &request,
+                "GetAccountAudit",
+                Some(&request.get_ref().trader_id),
+                &my_telemetry,
+            )
+            .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+
+            if accounts_cache
+                .get_account(&request.trader_id, &request.account_id)
+                .await
+                .is_none()
+            {
+                return service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                    Vec::<AccountAuditGrpcModel>::new().into_iter(),
+                    |x| x,
+                )
+                .await;
+            }
+
+            let records: Vec<AccountAuditGrpcModel> = self
+                .app
+                .account_audit_cache
+                .get(&request.account_id)
+                .await
+                .into_iter()
+                .filter(|x| x.trader_id == request.trader_id)
+                .map(|x| x.into())
+                .collect();
+
+            service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                records.into_iter(),
+                |x| x,
+            )
+            .await
+        })
+        .await
+    }
+
+    type GetActiveBonusesStream = Pin<
+        Box<dyn Stream<Item = Result<BonusGrantGrpcModel, tonic::Status>> + Send + Sync + 'static>,
+    >;
+
+    #[with_telemetry]
+    async fn get_active_bonuses(
+        &self,
+        request: Request<AccountManagerGetActiveBonusesGrpcRequest>,
+    ) -> Result<Response<Self::GetActiveBonusesStream>, Status> {
+        track_rpc("GetActiveBonuses", async {
+            self.authorize(
+                &request,
+                "GetActiveBonuses",
+                Some(&request.get_ref().trader_id),
+                &my_telemetry,
+            )
+            .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+
+            if accounts_cache
+                .get_account(&request.trader_id, &request.account_id)
+                .await
+                .is_none()
+            {
+                return service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                    Vec::<BonusGrantGrpcModel>::new().into_iter(),
+                    |x| x,
+                )
+                .await;
+            }
+
+            let grants: Vec<BonusGrantGrpcModel> = accounts_cache
+                .get_bonus_grants(&request.account_id)
+                .await
+                .into_iter()
+                .map(|x| x.into())
+                .collect();
+
+            service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                grants.into_iter(),
+                |x| x,
+            )
+            .await
+        })
+        .await
+    }
+
+    #[with_telemetry]
+    async fn get_balance_at(
+        &self,
+        request: Request<AccountManagerGetBalanceAtGrpcRequest>,
+    ) -> Result<Response<AccountManagerGetBalanceAtGrpcResponse>, Status> {
+        track_rpc("GetBalanceAt", async {
+            self.authorize(
+                &request,
+                "GetBalanceAt",
+                Some(&request.get_ref().trader_id),
+                &my_telemetry,
+            )
+            .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+
+            let entry = accounts_cache
+                .get_balance_at(&request.trader_id, &request.account_id, request.date)
+                .await?;
+
+            Ok(Response::new(entry.into()))
+        })
+        .await
+    }
+
+    type GetDailyBalancesStream = Pin<
+        Box<
+            dyn Stream<Item = Result<AccountDailyBalanceGrpcModel, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    #[with_telemetry]
+    async fn get_daily_balances(
+        &self,
+        request: Request<AccountManagerGetDailyBalancesGrpcRequest>,
+    ) -> Result<Response<Self::GetDailyBalancesStream>, Status> {
+        track_rpc("GetDailyBalances", async {
+            self.authorize(
+                &request,
+                "GetDailyBalances",
+                Some(&request.get_ref().trader_id),
+                &my_telemetry,
+            )
+            .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+
+            let cutoffs = self
+                .app
+                .daily_balances_cache
+                .get(
+                    accounts_cache.get_account_type(),
+                    request.date_from,
+                    request.date_to,
+                )
+                .await;
+
+            let balances: Vec<AccountDailyBalanceGrpcModel> = accounts_cache
+                .get_trader_balances_at(&request.trader_id, request.account_id.as_deref(), &cutoffs)
+                .await?
+                .into_iter()
+                .map(|x| x.into())
+                .collect();
+
+            service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                balances.into_iter(),
+                |x| x,
+            )
+            .await
+        })
+        .await
+    }
+
+    type GetStatementStream = Pin<
+        Box<
+            dyn Stream<Item = Result<AccountStatementRowGrpcModel, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    #[with_telemetry]
+    async fn get_statement(
+        &self,
+        request: Request<AccountManagerGetStatementGrpcRequest>,
+    ) -> Result<Response<Self::GetStatementStream>, Status> {
+        track_rpc("GetStatement", async {
+            self.authorize(
+                &request,
+                "GetStatement",
+                Some(&request.get_ref().trader_id),
+                &my_telemetry,
+            )
+            .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+
+            let statement = accounts_cache
+                .get_statement(
+                    &request.trader_id,
+                    &request.account_id,
+                    request.date_from,
+                    request.date_to,
+                )
+                .await?;
+
+            let rows: Vec<AccountStatementRowGrpcModel> =
+                statement.rows().into_iter().map(|x| x.into()).collect();
+
+            service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+                rows.into_iter(),
+                |x| x,
+            )
+            .await
+        })
+        .await
+    }
+
+    #[with_telemetry]
+    async fn export_statement(
+        &self,
+        request: Request<AccountManagerExportStatementGrpcRequest>,
+    ) -> Result<Response<AccountManagerExportStatementGrpcResponse>, Status> {
+        track_rpc("ExportStatement", async {
+            self.authorize(
+                &request,
+                "ExportStatement",
+                Some(&request.get_ref().trader_id),
+                &my_telemetry,
+            )
+            .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+
+            let statement = accounts_cache
+                .get_statement(
+                    &request.trader_id,
+                    &request.account_id,
+                    request.date_from,
+                    request.date_to,
+                )
+                .await?;
+
+            let content = match request.format() {
+                AccountStatementFormat::Csv => statement.to_csv(),
+                AccountStatementFormat::Json => statement.to_json(),
+            };
+
+            Ok(Response::new(AccountManagerExportStatementGrpcResponse {
+                content: content.into_bytes(),
+            }))
+        })
+        .await
+    }
+
+    #[with_telemetry]
+    async fn replay_ledger(
+        &self,
+        request: Request<AccountManagerReplayLedgerGrpcRequest>,
+    ) -> Result<Response<AccountManagerReplayLedgerGrpcResponse>, Status> {
+        track_rpc("ReplayLedger", async {
+            self.authorize(&request, "ReplayLedger", None, &my_telemetry)
+                .await?;
+
+            let request = request.into_inner();
+            request.validate()?;
+
+            let accounts_cache = self.app.accounts_caches.get(&request.account_type)?;
+            let (accounts_checked, mismatches) = accounts_cache.verify_ledger().await;
+
+            Ok(Response::new(AccountManagerReplayLedgerGrpcResponse {
+                accounts_checked: accounts_checked as u64,
+                mismatches: mismatches.into_iter().map(|x| x.into()).collect(),
+            }))
+        })
         .await
     }
+
     async fn ping(&self, _: tonic::Request<()>) -> Result<tonic::Response<()>, tonic::Status> {
         Ok(tonic::Response::new(()))
     }
 }
 
+fn balance_error_response(
+    error: OperationError,
+    operation_id: &str,
+) -> AccountManagerUpdateAccountBalanceGrpcResponse {
+    let mut response: AccountManagerUpdateAccountBalanceGrpcResponse = error.into();
+    response.operation_id = operation_id.to_string();
+    response
+}
+
+/// Status of a failed mutation. A status has no body, so the operation id
+/// goes into its metadata.
+fn get_error_status(error: OperationError, context: &OperationContext) -> tonic::Status {
+    let mut status = tonic::Status::from(error);
+
+    if let Ok(value) = context.operation_id.parse() {
+        status.metadata_mut().insert(OPERATION_ID_HEADER, value);
+    }
+
+    status
+}
+
+fn trade_log_request_received<T: serde::Serialize>(
+    trader_id: &str,
+    account_id: &str,
+    process_id: &str,
+    message: &str,
+    request: &T,
+    context: &OperationContext,
+    my_telemetry: &MyTelemetryContext,
+) {
+    trade_log::trade_log!(
+        trader_id,
+        account_id,
+        process_id,
+        &context.operation_id,
+        message,
+        my_telemetry.clone(),
+        "request" = request,
+        "operator_id" = &context.operator_id,
+        "caller" = &context.caller
+    );
+}
+
 fn get_accounts_vector(accounts: Option<Vec<Account>>) -> Vec<AccountGrpcModel> {
     match accounts {
         Some(accounts) => accounts
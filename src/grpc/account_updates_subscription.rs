@@ -0,0 +1,129 @@
+use std::{collections::HashSet, sync::Arc};
+
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio::sync::{broadcast::error::RecvError, mpsc};
+
+use crate::{
+    accounts_manager::{AccountGrpcModel, AccountManagerSubscribeAccountUpdatesGrpcRequest},
+    Account, AccountsCache,
+};
+
+const SUBSCRIBER_BUFFER_SIZE: usize = 1_000;
+
+pub struct AccountUpdatesFilter {
+    trader_ids: Vec<String>,
+    trading_group: Option<String>,
+}
+
+impl AccountUpdatesFilter {
+    pub fn matches(&self, account: &Account) -> bool {
+        if !self.trader_ids.is_empty() && !self.trader_ids.contains(&account.trader_id) {
+            return false;
+        }
+
+        if let Some(trading_group) = &self.trading_group {
+            if &account.trading_group != trading_group {
+                return false;
+            }
+        }
+
+        return true;
+    }
+}
+
+impl From<AccountManagerSubscribeAccountUpdatesGrpcRequest> for AccountUpdatesFilter {
+    fn from(request: AccountManagerSubscribeAccountUpdatesGrpcRequest) -> Self {
+        Self {
+            trader_ids: request.trader_ids,
+            trading_group: request.trading_group,
+        }
+    }
+}
+
+/// Streams a full snapshot of the matching accounts followed by every change.
+/// An account that stops matching, e.g. moved to another trading group, is
+/// sent once more in the state that no longer matches, so the subscriber can
+/// drop it.
+///
+/// The subscriber gets a bounded buffer: when it reads slower than accounts
+/// change, the cache broadcast overruns and the subscription resends a fresh
+/// snapshot instead of growing memory or stalling the cache writers.
+pub fn subscribe_account_updates(
+    accounts_cache: Arc<AccountsCache>,
+    filter: AccountUpdatesFilter,
+    my_telemetry: MyTelemetryContext,
+) -> mpsc::Receiver<Result<AccountGrpcModel, tonic::Status>> {
+    let (sender, receiver) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+
+    tokio::spawn(async move {
+        let mut updates = accounts_cache.subscribe_account_updates();
+        // Accounts the subscriber was last sent in a matching state.
+        let mut matching = HashSet::new();
+
+        if !send_snapshot(&accounts_cache, &filter, &mut matching, &sender).await {
+            return;
+        }
+
+        loop {
+            match updates.recv().await {
+                Ok(account) => {
+                    if !send_update(account, &filter, &mut matching, &sender).await {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    trade_log::trade_log!(
+                        "",
+                        "",
+                        "",
+                        "",
+                        "Account updates subscriber lagged. Resending snapshot.",
+                        my_telemetry.clone(),
+                        "skipped" = &skipped
+                    );
+
+                    if !send_snapshot(&accounts_cache, &filter, &mut matching, &sender).await {
+                        return;
+                    }
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    });
+
+    receiver
+}
+
+/// Sends the account if it matches or matched until this update. Returns
+/// false once the subscriber is gone.
+async fn send_update(
+    account: Account,
+    filter: &AccountUpdatesFilter,
+    matching: &mut HashSet<String>,
+    sender: &mpsc::Sender<Result<AccountGrpcModel, tonic::Status>>,
+) -> bool {
+    if filter.matches(&account) {
+        matching.insert(account.id.clone());
+    } else if !matching.remove(&account.id) {
+        return true;
+    }
+
+    return sender.send(Ok(account.into())).await.is_ok();
+}
+
+async fn send_snapshot(
+    accounts_cache: &AccountsCache,
+    filter: &AccountUpdatesFilter,
+    matching: &mut HashSet<String>,
+    sender: &mpsc::Sender<Result<AccountGrpcModel, tonic::Status>>,
+) -> bool {
+    let accounts = accounts_cache.get_all_accounts().await;
+
+    for account in accounts {
+        if !send_update(account, filter, matching, sender).await {
+            return false;
+        }
+    }
+
+    return true;
+}
@@ -0,0 +1,103 @@
+use crate::{
+    accounts_manager::UpdateBalanceReason, AuthSettingsModel, ServicePermissionsSettingsModel,
+};
+
+pub const API_KEY_HEADER: &str = "api-key";
+pub const CALLER_ID_HEADER: &str = "caller-id";
+
+const UNKNOWN_CALLER: &str = "unknown";
+const ALL_METHODS: &str = "*";
+
+#[derive(Debug, Clone)]
+pub struct CallerIdentity {
+    /// Service name of the api key owner, or the self-declared `caller-id`
+    /// when authentication is disabled.
+    pub name: String,
+    /// `None` when authentication is disabled and everything is allowed.
+    permissions: Option<ServicePermissionsSettingsModel>,
+}
+
+impl CallerIdentity {
+    pub fn ensure_method_allowed(&self, method: &str) -> Result<(), tonic::Status> {
+        let Some(permissions) = &self.permissions else {
+            return Ok(());
+        };
+
+        if permissions
+            .methods
+            .iter()
+            .any(|x| x == ALL_METHODS || x == method)
+        {
+            return Ok(());
+        }
+
+        Err(tonic::Status::permission_denied(format!(
+            "{} is not allowed to call {}",
+            self.name, method
+        )))
+    }
+
+    pub fn ensure_balance_reason_allowed(
+        &self,
+        reason: UpdateBalanceReason,
+    ) -> Result<(), tonic::Status> {
+        let Some(permissions) = &self.permissions else {
+            return Ok(());
+        };
+
+        let Some(balance_reasons) = &permissions.balance_reasons else {
+            return Ok(());
+        };
+
+        if balance_reasons.iter().any(|x| x == reason.as_str_name()) {
+            return Ok(());
+        }
+
+        Err(tonic::Status::permission_denied(format!(
+            "{} is not allowed to apply {} balance updates",
+            self.name,
+            reason.as_str_name()
+        )))
+    }
+}
+
+/// Resolves who is calling. With `auth` configured the `api-key` header must
+/// match one of the configured keys; without it the caller is trusted.
+pub fn authenticate<T>(
+    request: &tonic::Request<T>,
+    auth: Option<&AuthSettingsModel>,
+) -> Result<CallerIdentity, tonic::Status> {
+    let Some(auth) = auth else {
+        return Ok(CallerIdentity {
+            name: get_header(request, CALLER_ID_HEADER)
+                .unwrap_or(UNKNOWN_CALLER)
+                .to_string(),
+            permissions: None,
+        });
+    };
+
+    let Some(api_key) = get_header(request, API_KEY_HEADER) else {
+        return Err(tonic::Status::unauthenticated(format!(
+            "{} header is required",
+            API_KEY_HEADER
+        )));
+    };
+
+    let Some((service_name, _)) = auth.api_keys.iter().find(|(_, key)| *key == api_key) else {
+        return Err(tonic::Status::unauthenticated("Unknown api key"));
+    };
+
+    Ok(CallerIdentity {
+        name: service_name.clone(),
+        permissions: Some(auth.permissions.get(service_name).cloned().unwrap_or(
+            ServicePermissionsSettingsModel {
+                methods: vec![],
+                balance_reasons: Some(vec![]),
+            },
+        )),
+    })
+}
+
+fn get_header<'s, T>(request: &'s tonic::Request<T>, name: &str) -> Option<&'s str> {
+    request.metadata().get(name).and_then(|x| x.to_str().ok())
+}
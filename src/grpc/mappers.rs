@@ -1,26 +1,38 @@
-use cfd_engine_sb_contracts::AccountBalanceUpdateOperationType;
-
 use crate::{
     accounts_manager::{
-        AccountManagerGetClientAccountGrpcResponse, AccountsManagerOperationResult,
-        UpdateBalanceReason,
+        AccountAuditGrpcModel, AccountBalanceOperationGrpcModel, AccountDailyBalanceGrpcModel,
+        AccountManagerGetBalanceAtGrpcResponse, AccountManagerGetClientAccountGrpcResponse,
+        AccountManagerUpdateAccountBalanceGrpcResponse,
+        AccountManagerUpdateTradingDisabledGrpcResponse,
+        AccountManagerUpdateTradingGroupGrpcResponse, AccountStatementRowGrpcModel,
+        AccountsManagerOperationResult, BonusGrantGrpcModel, LedgerMismatchGrpcModel,
+        LedgerMismatchKind, UpdateBalanceReason,
     },
-    Account,
+    Account, AccountAuditRecord, AccountStatementRow, BalanceLedgerEntry, BonusGrant, DailyBalance,
+    LedgerMismatch, OperationError,
 };
+use cfd_engine_sb_contracts::AccountBalanceUpdateOperationType;
 
 impl Into<AccountBalanceUpdateOperationType> for UpdateBalanceReason {
     fn into(self) -> AccountBalanceUpdateOperationType {
         match self {
             UpdateBalanceReason::TradingResult => AccountBalanceUpdateOperationType::Trading,
-            UpdateBalanceReason::BalanceCorrection => AccountBalanceUpdateOperationType::BalanceCorrection,
+            UpdateBalanceReason::BalanceCorrection => {
+                AccountBalanceUpdateOperationType::BalanceCorrection
+            }
             UpdateBalanceReason::Deposit => AccountBalanceUpdateOperationType::Deposit,
             UpdateBalanceReason::Withdrawal => AccountBalanceUpdateOperationType::Withdrawal,
-            UpdateBalanceReason::WithdrawalCanceled => AccountBalanceUpdateOperationType::WithdrawalCanceled,
+            UpdateBalanceReason::WithdrawalCanceled => {
+                AccountBalanceUpdateOperationType::WithdrawalCanceled
+            }
             UpdateBalanceReason::ToppingUp => AccountBalanceUpdateOperationType::ToppingUp,
             UpdateBalanceReason::Dividends => AccountBalanceUpdateOperationType::Dividends,
             UpdateBalanceReason::Bonus => AccountBalanceUpdateOperationType::Bonus,
             UpdateBalanceReason::Credit => AccountBalanceUpdateOperationType::Credit,
             UpdateBalanceReason::Voucher => AccountBalanceUpdateOperationType::Voucher,
+            UpdateBalanceReason::BonusConversion => {
+                AccountBalanceUpdateOperationType::BonusConversion
+            }
         }
     }
 }
@@ -31,11 +43,175 @@ impl Into<AccountManagerGetClientAccountGrpcResponse> for Option<Account> {
             Some(account) => AccountManagerGetClientAccountGrpcResponse {
                 result: AccountsManagerOperationResult::Ok as i32,
                 account: Some(account.into()),
+                error_message: None,
+            },
+            None => OperationError::AccountNofFound.into(),
+        }
+    }
+}
+
+impl From<OperationError> for AccountManagerGetClientAccountGrpcResponse {
+    fn from(error: OperationError) -> Self {
+        Self {
+            result: error.as_grpc_error(),
+            account: None,
+            error_message: Some(error.get_message()),
+        }
+    }
+}
+
+impl From<OperationError> for AccountManagerUpdateAccountBalanceGrpcResponse {
+    fn from(error: OperationError) -> Self {
+        Self {
+            result: error.as_grpc_error(),
+            update_balance_info: None,
+            error_message: Some(error.get_message()),
+            operation_id: String::new(),
+        }
+    }
+}
+
+impl From<OperationError> for AccountManagerUpdateTradingDisabledGrpcResponse {
+    fn from(error: OperationError) -> Self {
+        Self {
+            result: error.as_grpc_error(),
+            account: None,
+            error_message: Some(error.get_message()),
+            previous_trading_disabled: None,
+            operation_id: String::new(),
+        }
+    }
+}
+
+impl From<OperationError> for AccountManagerUpdateTradingGroupGrpcResponse {
+    fn from(error: OperationError) -> Self {
+        Self {
+            result: error.as_grpc_error(),
+            account: None,
+            error_message: Some(error.get_message()),
+            previous_trading_group: None,
+            operation_id: String::new(),
+        }
+    }
+}
+
+impl Into<AccountAuditGrpcModel> for AccountAuditRecord {
+    fn into(self) -> AccountAuditGrpcModel {
+        AccountAuditGrpcModel {
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            field: self.field,
+            old_value: self.old_value,
+            new_value: self.new_value,
+            process_id: self.process_id,
+            operator_id: self.operator_id,
+            date: self.date,
+            operation_id: self.operation_id,
+        }
+    }
+}
+
+impl Into<AccountBalanceOperationGrpcModel> for BalanceLedgerEntry {
+    fn into(self) -> AccountBalanceOperationGrpcModel {
+        AccountBalanceOperationGrpcModel {
+            sequence: self.sequence,
+            previous_balance: self.previous_balance,
+            delta: self.delta,
+            new_balance: self.new_balance,
+            reason: self.reason.map(|x| x as i32),
+            process_id: self.process_id,
+            operation_id: self.operation_id,
+            date: self.date,
+            comment: self.comment,
+            reference_transaction_id: self.reference_transaction_id,
+        }
+    }
+}
+
+impl Into<AccountManagerGetBalanceAtGrpcResponse> for BalanceLedgerEntry {
+    fn into(self) -> AccountManagerGetBalanceAtGrpcResponse {
+        AccountManagerGetBalanceAtGrpcResponse {
+            balance: self.new_balance,
+            last_operation: Some(self.into()),
+        }
+    }
+}
+
+impl Into<LedgerMismatchGrpcModel> for LedgerMismatch {
+    fn into(self) -> LedgerMismatchGrpcModel {
+        match self {
+            LedgerMismatch::BrokenChain {
+                account_id,
+                sequence,
+            } => LedgerMismatchGrpcModel {
+                account_id,
+                kind: LedgerMismatchKind::BrokenChain as i32,
+                sequence: Some(sequence),
+                cached_balance: None,
+                replayed_balance: None,
+            },
+            LedgerMismatch::MissingEntries { account_id } => LedgerMismatchGrpcModel {
+                account_id,
+                kind: LedgerMismatchKind::MissingEntries as i32,
+                sequence: None,
+                cached_balance: None,
+                replayed_balance: None,
             },
-            None => AccountManagerGetClientAccountGrpcResponse {
-                result: AccountsManagerOperationResult::AccountNotFound as i32,
-                account: None,
+            LedgerMismatch::BalanceMismatch {
+                account_id,
+                cached,
+                replayed,
+            } => LedgerMismatchGrpcModel {
+                account_id,
+                kind: LedgerMismatchKind::BalanceMismatch as i32,
+                sequence: None,
+                cached_balance: Some(cached),
+                replayed_balance: Some(replayed),
             },
         }
     }
 }
+
+impl Into<AccountDailyBalanceGrpcModel> for DailyBalance {
+    fn into(self) -> AccountDailyBalanceGrpcModel {
+        AccountDailyBalanceGrpcModel {
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            currency: self.currency,
+            balance: self.balance,
+            date: self.date,
+        }
+    }
+}
+
+impl Into<BonusGrantGrpcModel> for BonusGrant {
+    fn into(self) -> BonusGrantGrpcModel {
+        BonusGrantGrpcModel {
+            id: self.id,
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            amount: self.amount,
+            granted_at: self.granted_at,
+            expires_at: self.expires_at,
+            turnover_required: self.turnover_required,
+            turnover: self.turnover,
+            status: self.status as i32,
+        }
+    }
+}
+
+impl Into<AccountStatementRowGrpcModel> for AccountStatementRow {
+    fn into(self) -> AccountStatementRowGrpcModel {
+        AccountStatementRowGrpcModel {
+            row_type: self.row_type as i32,
+            date: self.date,
+            reason: self.reason.map(|x| x as i32),
+            delta: self.delta,
+            balance: self.balance,
+            comment: self.comment,
+            reference_transaction_id: self.reference_transaction_id,
+            process_id: self.process_id,
+            operation_id: self.operation_id,
+        }
+    }
+}
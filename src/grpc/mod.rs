@@ -1,8 +1,20 @@
 mod account_manager_grpc;
+mod account_updates_subscription;
+mod caller_auth;
 mod server;
 
 mod mappers;
+mod operation_context;
 mod process_id_cache;
+mod rate_limiter;
+mod request_validation;
+mod rpc_metrics;
 
 pub use server::*;
+pub use account_updates_subscription::*;
+pub use caller_auth::*;
+pub use operation_context::*;
 pub use process_id_cache::*;
+pub use rate_limiter::*;
+pub use request_validation::*;
+pub use rpc_metrics::*;
@@ -0,0 +1,19 @@
+use crate::OperationContext;
+
+use super::CallerIdentity;
+
+const OPERATOR_ID_HEADER: &str = "operator-id";
+pub const OPERATION_ID_HEADER: &str = "operation-id";
+
+pub fn get_operation_context<T>(
+    request: &tonic::Request<T>,
+    caller: &CallerIdentity,
+) -> OperationContext {
+    let operator_id = request
+        .metadata()
+        .get(OPERATOR_ID_HEADER)
+        .and_then(|x| x.to_str().ok())
+        .map(|x| x.to_string());
+
+    OperationContext::new(operator_id, caller.name.clone())
+}
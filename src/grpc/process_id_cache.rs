@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use tokio::sync::Mutex;
 
+use crate::set_process_id_cache_size;
+
 pub struct ProcessIdCache<T: Clone> {
     cache: Mutex<HashMap<String, T>>,
 }
@@ -21,5 +23,6 @@ impl<T: Clone> ProcessIdCache<T> {
     pub async fn set(&self, key: &str, value: T) {
         let mut cache = self.cache.lock().await;
         cache.insert(key.to_string(), value);
+        set_process_id_cache_size(cache.len());
     }
 }
@@ -0,0 +1,170 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Mutex;
+
+use crate::{RateLimitSettingsModel, RateLimitsSettingsModel};
+
+pub const RETRY_AFTER_HEADER: &str = "retry-after-ms";
+
+const CLEANUP_THRESHOLD: usize = 10_000;
+
+pub struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(settings: &RateLimitSettingsModel, now: Instant) -> Self {
+        Self {
+            tokens: settings.burst,
+            updated: now,
+        }
+    }
+
+    /// Takes one token or returns how long to wait until one is available.
+    pub fn try_acquire(
+        &mut self,
+        settings: &RateLimitSettingsModel,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        self.check(settings, now)?;
+        self.take();
+        Ok(())
+    }
+
+    /// Whether a token is available, without taking it; otherwise how long
+    /// to wait until one is.
+    pub fn check(
+        &mut self,
+        settings: &RateLimitSettingsModel,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        self.refill(settings, now);
+
+        if self.tokens >= 1.0 {
+            return Ok(());
+        }
+
+        if settings.requests_per_second <= 0.0 {
+            return Err(Duration::MAX);
+        }
+
+        let missing = 1.0 - self.tokens;
+        Err(Duration::from_secs_f64(
+            missing / settings.requests_per_second,
+        ))
+    }
+
+    pub fn take(&mut self) {
+        self.tokens -= 1.0;
+    }
+
+    pub fn is_full(&mut self, settings: &RateLimitSettingsModel, now: Instant) -> bool {
+        self.refill(settings, now);
+        self.tokens >= settings.burst
+    }
+
+    fn refill(&mut self, settings: &RateLimitSettingsModel, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * settings.requests_per_second).min(settings.burst);
+        self.updated = now;
+    }
+}
+
+pub struct RateLimiter {
+    traders: Mutex<HashMap<String, TokenBucket>>,
+    callers: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self {
+            traders: Mutex::new(HashMap::new()),
+            callers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks the caller limit and, when the request targets a trader, the
+    /// trader limit. A token is taken from either only when both allow the
+    /// request, so a rejected request does not use up the other limit.
+    pub async fn check(
+        &self,
+        caller_id: &str,
+        trader_id: Option<&str>,
+        settings: &RateLimitsSettingsModel,
+    ) -> Result<(), tonic::Status> {
+        let now = Instant::now();
+
+        // Always locked in this order.
+        let mut callers = self.callers.lock().await;
+        let mut traders = self.traders.lock().await;
+
+        let caller = settings.per_caller.as_ref().map(|limit| {
+            (
+                "caller",
+                caller_id,
+                limit,
+                get_bucket(&mut callers, caller_id, limit, now),
+            )
+        });
+
+        let trader = match (&settings.per_trader, trader_id) {
+            (Some(limit), Some(trader_id)) => Some((
+                "trader",
+                trader_id,
+                limit,
+                get_bucket(&mut traders, trader_id, limit, now),
+            )),
+            _ => None,
+        };
+
+        let mut buckets: Vec<_> = caller.into_iter().chain(trader).collect();
+
+        for (kind, key, limit, bucket) in buckets.iter_mut() {
+            if let Err(retry_after) = bucket.check(limit, now) {
+                return Err(rate_limited_status(kind, key, retry_after));
+            }
+        }
+
+        for (_, _, _, bucket) in buckets {
+            bucket.take();
+        }
+
+        Ok(())
+    }
+}
+
+fn get_bucket<'s>(
+    buckets: &'s mut HashMap<String, TokenBucket>,
+    key: &str,
+    settings: &RateLimitSettingsModel,
+    now: Instant,
+) -> &'s mut TokenBucket {
+    // A full bucket behaves exactly like a missing one, so dropping them
+    // keeps memory bounded without loosening any limit.
+    if buckets.len() > CLEANUP_THRESHOLD {
+        buckets.retain(|_, bucket| !bucket.is_full(settings, now));
+    }
+
+    buckets
+        .entry(key.to_string())
+        .or_insert_with(|| TokenBucket::new(settings, now))
+}
+
+fn rate_limited_status(kind: &str, key: &str, retry_after: Duration) -> tonic::Status {
+    let retry_after_ms = retry_after.as_millis().min(u64::MAX as u128) as u64;
+
+    let mut status = tonic::Status::resource_exhausted(format!(
+        "Rate limit exceeded for {} {}. Retry after {} ms",
+        kind, key, retry_after_ms
+    ));
+
+    if let Ok(value) = retry_after_ms.to_string().parse() {
+        status.metadata_mut().insert(RETRY_AFTER_HEADER, value);
+    }
+
+    status
+}
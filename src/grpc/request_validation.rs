@@ -0,0 +1,425 @@
+use crate::{
+    accounts_manager::{
+        AccountManagerBulkUpdateTradingDisabledGrpcRequest,
+        AccountManagerBulkUpdateTradingGroupGrpcRequest, AccountManagerCreateAccountGrpcRequest,
+        AccountManagerEnsureDefaultAccountsGrpcRequest, AccountManagerExportStatementGrpcRequest,
+        AccountManagerGetAccountAuditGrpcRequest, AccountManagerGetAccountsByGroupGrpcRequest,
+        AccountManagerGetActiveBonusesGrpcRequest, AccountManagerGetBalanceAtGrpcRequest,
+        AccountManagerGetClientAccountGrpcRequest, AccountManagerGetClientAccountsGrpcRequest,
+        AccountManagerGetDailyBalancesGrpcRequest, AccountManagerGetStatementGrpcRequest,
+        AccountManagerGetTraderIdByAccountIdGrpcRequest, AccountManagerReplayLedgerGrpcRequest,
+        AccountManagerSubscribeAccountUpdatesGrpcRequest,
+        AccountManagerUpdateAccountBalanceGrpcRequest,
+        AccountManagerUpdateAccountMetadataGrpcRequest,
+        AccountManagerUpdateTradingDisabledGrpcRequest,
+        AccountManagerUpdateTradingGroupGrpcRequest, AccountMetadataItemGrpcModel,
+        AccountStatementFormat, FromToInt64Model, SearchAccounts, TradingDisabledReason,
+        UpdateBalanceReason,
+    },
+    OperationError,
+};
+
+pub trait ValidateRequest {
+    fn validate(&self) -> Result<(), OperationError>;
+}
+
+impl ValidateRequest for AccountManagerCreateAccountGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("currency", &self.currency)?;
+        validate_not_empty("process_id", &self.process_id)?;
+
+        if let Some(trading_group_id) = &self.trading_group_id {
+            validate_not_empty("trading_group_id", trading_group_id)?;
+        }
+
+        validate_metadata(&self.metadata)
+    }
+}
+
+impl ValidateRequest for AccountManagerUpdateAccountBalanceGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("account_id", &self.account_id)?;
+        validate_not_empty("process_id", &self.process_id)?;
+
+        if !self.delta.is_finite() {
+            return Err(OperationError::InvalidRequest(format!(
+                "delta must be a finite number, got {}",
+                self.delta
+            )));
+        }
+
+        if self.delta == 0.0 {
+            return Err(OperationError::InvalidRequest(
+                "delta must not be zero".to_string(),
+            ));
+        }
+
+        if UpdateBalanceReason::try_from(self.reason).is_err() {
+            return Err(OperationError::InvalidRequest(format!(
+                "reason {} is not a known UpdateBalanceReason",
+                self.reason
+            )));
+        }
+
+        if let Some(reference_transaction_id) = &self.reference_transaction_id {
+            validate_not_empty("reference_transaction_id", reference_transaction_id)?;
+        }
+
+        // Only earned grants are converted, by the bonus job, which applies
+        // the update without this validation.
+        if self.reason == UpdateBalanceReason::BonusConversion as i32 {
+            return Err(OperationError::InvalidRequest(
+                "reason BonusConversion is reserved for converting earned bonuses".to_string(),
+            ));
+        }
+
+        let is_bonus_grant = (self.reason == UpdateBalanceReason::Bonus as i32
+            || self.reason == UpdateBalanceReason::Voucher as i32)
+            && self.delta > 0.0;
+
+        if !is_bonus_grant
+            && (self.bonus_expires_at.is_some() || self.bonus_turnover_required.is_some())
+        {
+            return Err(OperationError::InvalidRequest(
+                "bonus_expires_at and bonus_turnover_required are only allowed when granting a bonus"
+                    .to_string(),
+            ));
+        }
+
+        if let Some(turnover_required) = self.bonus_turnover_required {
+            if !turnover_required.is_finite() || turnover_required <= 0.0 {
+                return Err(OperationError::InvalidRequest(format!(
+                    "bonus_turnover_required must be a positive number, got {}",
+                    turnover_required
+                )));
+            }
+        }
+
+        if let Some(trading_volume) = self.trading_volume {
+            if self.reason != UpdateBalanceReason::TradingResult as i32 {
+                return Err(OperationError::InvalidRequest(
+                    "trading_volume is only allowed for a trading result".to_string(),
+                ));
+            }
+
+            if !trading_volume.is_finite() || trading_volume < 0.0 {
+                return Err(OperationError::InvalidRequest(format!(
+                    "trading_volume must be a non-negative number, got {}",
+                    trading_volume
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateRequest for AccountManagerGetClientAccountGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("account_id", &self.account_id)
+    }
+}
+
+impl ValidateRequest for AccountManagerGetAccountAuditGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("account_id", &self.account_id)
+    }
+}
+
+impl ValidateRequest for AccountManagerGetActiveBonusesGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("account_id", &self.account_id)
+    }
+}
+
+impl ValidateRequest for AccountManagerGetClientAccountsGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)
+    }
+}
+
+impl ValidateRequest for AccountManagerGetTraderIdByAccountIdGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("account_id", &self.account_id)
+    }
+}
+
+impl ValidateRequest for AccountManagerGetAccountsByGroupGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trading_group", &self.trading_group)
+    }
+}
+
+impl ValidateRequest for AccountManagerGetBalanceAtGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("account_id", &self.account_id)?;
+
+        if self.date == 0 {
+            return Err(OperationError::InvalidRequest(
+                "date must be set".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateRequest for AccountManagerGetDailyBalancesGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+
+        if let Some(account_id) = &self.account_id {
+            validate_not_empty("account_id", account_id)?;
+        }
+
+        if self.date_from > self.date_to {
+            return Err(OperationError::InvalidRequest(
+                "date_from must not be after date_to".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateRequest for AccountManagerGetStatementGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_statement_period(
+            &self.trader_id,
+            &self.account_id,
+            self.date_from,
+            self.date_to,
+        )
+    }
+}
+
+impl ValidateRequest for AccountManagerExportStatementGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_statement_period(
+            &self.trader_id,
+            &self.account_id,
+            self.date_from,
+            self.date_to,
+        )?;
+
+        if AccountStatementFormat::try_from(self.format).is_err() {
+            return Err(OperationError::InvalidRequest(format!(
+                "format {} is not a known AccountStatementFormat",
+                self.format
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateRequest for AccountManagerReplayLedgerGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        // An empty account type replays the default one.
+        if !self.account_type.is_empty() {
+            validate_not_empty("account_type", &self.account_type)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateRequest for AccountManagerEnsureDefaultAccountsGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("process_id", &self.process_id)
+    }
+}
+
+impl ValidateRequest for SearchAccounts {
+    fn validate(&self) -> Result<(), OperationError> {
+        for trader_id in &self.trader_ids {
+            validate_not_empty("trader_ids", trader_id)?;
+        }
+
+        if let Some(currency) = &self.currency {
+            validate_not_empty("currency", currency)?;
+        }
+
+        if let Some(trading_group) = &self.trading_group {
+            validate_not_empty("trading_group", trading_group)?;
+        }
+
+        validate_range("created", self.created.as_ref())?;
+        validate_range("balance", self.balance.as_ref())
+    }
+}
+
+impl ValidateRequest for AccountManagerBulkUpdateTradingGroupGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_bulk_filter(self.filter.as_ref(), self.all_accounts)?;
+        validate_not_empty("new_trading_group", &self.new_trading_group)?;
+        validate_not_empty("process_id", &self.process_id)
+    }
+}
+
+impl ValidateRequest for AccountManagerBulkUpdateTradingDisabledGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_bulk_filter(self.filter.as_ref(), self.all_accounts)?;
+        validate_not_empty("process_id", &self.process_id)?;
+        validate_trading_disabled_reason(self.reason)
+    }
+}
+
+impl ValidateRequest for AccountManagerSubscribeAccountUpdatesGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        for trader_id in &self.trader_ids {
+            validate_not_empty("trader_ids", trader_id)?;
+        }
+
+        if let Some(trading_group) = &self.trading_group {
+            validate_not_empty("trading_group", trading_group)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateRequest for AccountManagerUpdateTradingDisabledGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("account_id", &self.account_id)?;
+        validate_not_empty("process_id", &self.process_id)?;
+        validate_trading_disabled_reason(self.reason)
+    }
+}
+
+impl ValidateRequest for AccountManagerUpdateTradingGroupGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("account_id", &self.account_id)?;
+        validate_not_empty("new_trading_group", &self.new_trading_group)?;
+        validate_not_empty("process_id", &self.process_id)
+    }
+}
+
+fn validate_not_empty(field_name: &str, value: &str) -> Result<(), OperationError> {
+    if value.trim().is_empty() {
+        return Err(OperationError::InvalidRequest(format!(
+            "{} must not be empty",
+            field_name
+        )));
+    }
+
+    Ok(())
+}
+
+impl ValidateRequest for AccountManagerUpdateAccountMetadataGrpcRequest {
+    fn validate(&self) -> Result<(), OperationError> {
+        validate_not_empty("trader_id", &self.trader_id)?;
+        validate_not_empty("account_id", &self.account_id)?;
+        validate_not_empty("process_id", &self.process_id)?;
+        validate_metadata(&self.metadata)
+    }
+}
+
+fn validate_trading_disabled_reason(reason: i32) -> Result<(), OperationError> {
+    if TradingDisabledReason::try_from(reason).is_err() {
+        return Err(OperationError::InvalidRequest(format!(
+            "reason {} is not a known TradingDisabledReason",
+            reason
+        )));
+    }
+
+    Ok(())
+}
+
+/// A filter without criteria matches every account of the type, so it is only
+/// taken together with `all_accounts`.
+fn validate_bulk_filter(
+    filter: Option<&SearchAccounts>,
+    all_accounts: bool,
+) -> Result<(), OperationError> {
+    let Some(filter) = filter else {
+        return Err(OperationError::InvalidRequest(
+            "filter must be set for bulk operations".to_string(),
+        ));
+    };
+
+    filter.validate()?;
+
+    if !all_accounts && !has_criteria(filter) {
+        return Err(OperationError::InvalidRequest(
+            "filter has no criteria; set all_accounts to update every account".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn has_criteria(filter: &SearchAccounts) -> bool {
+    let has_range = |range: Option<&FromToInt64Model>| {
+        range.is_some_and(|x| x.from.is_some() || x.to.is_some())
+    };
+
+    !filter.trader_ids.is_empty()
+        || filter.currency.is_some()
+        || has_range(filter.created.as_ref())
+        || has_range(filter.balance.as_ref())
+        || filter.disabled.is_some()
+        || filter.trading_group.is_some()
+}
+
+fn validate_metadata(metadata: &[AccountMetadataItemGrpcModel]) -> Result<(), OperationError> {
+    for (index, item) in metadata.iter().enumerate() {
+        validate_not_empty("metadata.key", &item.key)?;
+
+        if metadata[..index].iter().any(|x| x.key == item.key) {
+            return Err(OperationError::InvalidRequest(format!(
+                "metadata key {} is duplicated",
+                item.key
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_range(
+    field_name: &str,
+    range: Option<&FromToInt64Model>,
+) -> Result<(), OperationError> {
+    let Some(range) = range else {
+        return Ok(());
+    };
+
+    if let (Some(from), Some(to)) = (range.from, range.to) {
+        if from > to {
+            return Err(OperationError::InvalidRequest(format!(
+                "{}.from ({}) must not be greater than {}.to ({})",
+                field_name, from, field_name, to
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_statement_period(
+    trader_id: &str,
+    account_id: &str,
+    date_from: u64,
+    date_to: u64,
+) -> Result<(), OperationError> {
+    validate_not_empty("trader_id", trader_id)?;
+    validate_not_empty("account_id", account_id)?;
+
+    if date_from > date_to {
+        return Err(OperationError::InvalidRequest(
+            "date_from must not be after date_to".to_string(),
+        ));
+    }
+
+    Ok(())
+}
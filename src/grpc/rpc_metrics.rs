@@ -0,0 +1,140 @@
+use std::{future::Future, pin::Pin, time::Instant};
+
+use crate::{
+    accounts_manager::{
+        AccountGrpcModel, AccountManagerBulkUpdateGrpcResponse,
+        AccountManagerEnsureDefaultAccountsGrpcResponse, AccountManagerExportStatementGrpcResponse,
+        AccountManagerGetBalanceAtGrpcResponse, AccountManagerGetClientAccountGrpcResponse,
+        AccountManagerGetTraderIdByAccountIdGrpcResponse, AccountManagerReplayLedgerGrpcResponse,
+        AccountManagerUpdateAccountBalanceGrpcResponse,
+        AccountManagerUpdateAccountMetadataGrpcResponse,
+        AccountManagerUpdateTradingDisabledGrpcResponse,
+        AccountManagerUpdateTradingGroupGrpcResponse, AccountsManagerOperationResult,
+    },
+    observe_rpc,
+};
+
+pub trait RpcResultCode {
+    fn get_result_code(&self) -> &'static str;
+}
+
+/// Records latency and result code of a handler. For streaming methods the
+/// latency covers opening the stream, not reading it.
+pub async fn track_rpc<T: RpcResultCode>(
+    method: &'static str,
+    handler: impl Future<Output = Result<tonic::Response<T>, tonic::Status>>,
+) -> Result<tonic::Response<T>, tonic::Status> {
+    let started = Instant::now();
+    let result = handler.await;
+
+    let result_code = match &result {
+        Ok(response) => response.get_ref().get_result_code(),
+        Err(status) => get_status_code_name(status.code()),
+    };
+
+    observe_rpc(method, result_code, started);
+
+    result
+}
+
+fn get_operation_result_name(result: i32) -> &'static str {
+    match AccountsManagerOperationResult::try_from(result) {
+        Ok(result) => result.as_str_name(),
+        Err(_) => "Unknown",
+    }
+}
+
+fn get_status_code_name(code: tonic::Code) -> &'static str {
+    match code {
+        tonic::Code::Ok => "Ok",
+        tonic::Code::InvalidArgument => "InvalidArgument",
+        tonic::Code::NotFound => "NotFound",
+        tonic::Code::AlreadyExists => "AlreadyExists",
+        tonic::Code::FailedPrecondition => "FailedPrecondition",
+        tonic::Code::Aborted => "Aborted",
+        tonic::Code::ResourceExhausted => "ResourceExhausted",
+        tonic::Code::Unauthenticated => "Unauthenticated",
+        tonic::Code::PermissionDenied => "PermissionDenied",
+        tonic::Code::Unavailable => "Unavailable",
+        tonic::Code::Internal => "Internal",
+        _ => "Unknown",
+    }
+}
+
+impl RpcResultCode for AccountManagerGetClientAccountGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        get_operation_result_name(self.result)
+    }
+}
+
+impl RpcResultCode for AccountManagerUpdateAccountBalanceGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        get_operation_result_name(self.result)
+    }
+}
+
+impl RpcResultCode for AccountManagerUpdateTradingDisabledGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        get_operation_result_name(self.result)
+    }
+}
+
+impl RpcResultCode for AccountManagerUpdateTradingGroupGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        get_operation_result_name(self.result)
+    }
+}
+
+impl RpcResultCode for AccountManagerUpdateAccountMetadataGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
+
+impl RpcResultCode for AccountManagerEnsureDefaultAccountsGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
+
+impl RpcResultCode for AccountManagerBulkUpdateGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
+
+impl RpcResultCode for AccountManagerGetBalanceAtGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
+
+impl RpcResultCode for AccountManagerReplayLedgerGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
+
+impl RpcResultCode for AccountManagerExportStatementGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
+
+impl RpcResultCode for AccountGrpcModel {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
+
+impl RpcResultCode for AccountManagerGetTraderIdByAccountIdGrpcResponse {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
+
+impl<T: ?Sized> RpcResultCode for Pin<Box<T>> {
+    fn get_result_code(&self) -> &'static str {
+        "Ok"
+    }
+}
@@ -1,9 +1,12 @@
 use crate::accounts_manager::accounts_manager_grpc_service_server::AccountsManagerGrpcServiceServer;
 use crate::AppContext;
+use service_sdk::my_telemetry::MyTelemetryContext;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tonic::transport::Server;
 
+use super::{authenticate, CallerIdentity};
+
 #[derive(Clone)]
 pub struct GrpcService {
     pub app: Arc<AppContext>,
@@ -13,6 +16,53 @@ impl GrpcService {
     pub fn new(app: Arc<AppContext>) -> Self {
         Self { app }
     }
+
+    /// Authenticates the caller, checks it may call `method` and applies the
+    /// rate limits. Rejections are written to the trade log.
+    pub async fn authorize<T>(
+        &self,
+        request: &tonic::Request<T>,
+        method: &'static str,
+        trader_id: Option<&str>,
+        my_telemetry: &MyTelemetryContext,
+    ) -> Result<CallerIdentity, tonic::Status> {
+        let result = self.check_caller(request, method, trader_id).await;
+
+        if let Err(status) = &result {
+            trade_log::trade_log!(
+                trader_id.unwrap_or(""),
+                "",
+                "",
+                "",
+                "Rejected request.",
+                my_telemetry.clone(),
+                "method" = &method,
+                "code" = &format!("{:?}", status.code()),
+                "message" = &status.message()
+            );
+        }
+
+        result
+    }
+
+    async fn check_caller<T>(
+        &self,
+        request: &tonic::Request<T>,
+        method: &'static str,
+        trader_id: Option<&str>,
+    ) -> Result<CallerIdentity, tonic::Status> {
+        let auth = self.app.settings.get_auth().await;
+        let caller = authenticate(request, auth.as_ref())?;
+        caller.ensure_method_allowed(method)?;
+
+        let rate_limits = self.app.settings.get_rate_limits().await;
+        self.app
+            .rate_limiter
+            .check(&caller.name, trader_id, &rate_limits)
+            .await?;
+
+        Ok(caller)
+    }
 }
 
 pub async fn start_grpc_server(app: Arc<AppContext>, port: u16) {
@@ -3,8 +3,13 @@ mod background;
 mod caches;
 mod grpc;
 mod grpc_client;
+mod local_persistence;
 mod settings;
 mod flows;
+mod metrics;
+mod operation_error;
+mod sb_contracts;
+mod validated_settings;
 
 pub mod accounts_manager {
     tonic::include_proto!("accounts_manager");
@@ -20,4 +25,9 @@ pub use background::*;
 pub use caches::*;
 pub use grpc::*;
 pub use grpc_client::*;
+pub use local_persistence::*;
 pub use settings::*;
+pub use metrics::*;
+pub use operation_error::*;
+pub use sb_contracts::*;
+pub use validated_settings::*;
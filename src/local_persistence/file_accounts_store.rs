@@ -0,0 +1,388 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use cfd_engine_sb_contracts::{
+    AccountAuditRecordSbModel, AccountPersistEvent, AccountSbModel, BalanceLedgerEntrySbModel,
+    BonusGrantSbModel,
+};
+use serde::{Deserialize, Serialize};
+use service_sdk::async_trait;
+use tokio::{
+    io::AsyncWriteExt,
+    sync::{mpsc, Mutex},
+};
+
+use crate::{
+    accounts_manager::BonusGrantStatus,
+    accounts_manager_persistence::{
+        AccountMetadataItemGrpcModel, PersistenceAccountGrpcModel, PersistenceAuditRecordGrpcModel,
+        PersistenceBalanceLedgerEntryGrpcModel, PersistenceBonusGrantGrpcModel,
+        PersistenceTradingDisabledReasonGrpcModel,
+    },
+    Account, AccountAuditRecord, AccountsLoader, BalanceLedgerEntry, BonusGrant,
+    DailyBalancesSbEvent, LocalPersistEvent,
+};
+
+/// What is stored for one account type.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredAccountType {
+    /// Last state of every account by account id.
+    accounts: BTreeMap<String, PersistenceAccountGrpcModel>,
+    audit_records: Vec<PersistenceAuditRecordGrpcModel>,
+    /// Ledger entries of every account in the order they were applied.
+    #[serde(default)]
+    ledger: Vec<PersistenceBalanceLedgerEntryGrpcModel>,
+    #[serde(default)]
+    daily_balances_cutoffs: BTreeSet<u64>,
+    /// Open bonus grants by grant id; a closed grant is removed.
+    #[serde(default)]
+    bonus_grants: BTreeMap<String, PersistenceBonusGrantGrpcModel>,
+}
+
+/// Account type -> stored state, as kept in the file.
+type StoredAccounts = HashMap<String, StoredAccountType>;
+
+/// JSON file with the persisted state of every account. Stands in for the
+/// persistence service when developing and testing without the platform.
+pub struct FileAccountsStore {
+    path: PathBuf,
+    accounts: Mutex<StoredAccounts>,
+}
+
+impl FileAccountsStore {
+    /// Reads the file if it exists; a missing file is an empty store.
+    pub async fn open(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+
+        let accounts = match tokio::fs::read(&path).await {
+            Ok(content) => serde_json::from_slice(&content).map_err(|err| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Can not parse {}: {}", path.display(), err),
+                )
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Self {
+            path,
+            accounts: Mutex::new(accounts),
+        })
+    }
+
+    pub async fn get_all(&self, account_type: &str) -> Vec<PersistenceAccountGrpcModel> {
+        let accounts = self.accounts.lock().await;
+
+        match accounts.get(account_type) {
+            Some(stored) => stored.accounts.values().cloned().collect(),
+            None => vec![],
+        }
+    }
+
+    pub async fn get_audit_records(
+        &self,
+        account_type: &str,
+    ) -> Vec<PersistenceAuditRecordGrpcModel> {
+        let accounts = self.accounts.lock().await;
+
+        match accounts.get(account_type) {
+            Some(stored) => stored.audit_records.clone(),
+            None => vec![],
+        }
+    }
+
+    /// Entries dated at or after `date_from`, preceded by the last earlier
+    /// entry of every account.
+    pub async fn get_balance_ledger(
+        &self,
+        account_type: &str,
+        date_from: u64,
+    ) -> Vec<PersistenceBalanceLedgerEntryGrpcModel> {
+        let accounts = self.accounts.lock().await;
+
+        let Some(stored) = accounts.get(account_type) else {
+            return vec![];
+        };
+
+        let mut chain_starts = HashMap::new();
+        let mut result = vec![];
+
+        for entry in &stored.ledger {
+            if entry.date < date_from {
+                chain_starts.insert(entry.account_id.clone(), entry.clone());
+            } else {
+                result.push(entry.clone());
+            }
+        }
+
+        chain_starts.into_values().chain(result).collect()
+    }
+
+    pub async fn get_daily_balances_cutoffs(&self, account_type: &str) -> Vec<u64> {
+        let accounts = self.accounts.lock().await;
+
+        match accounts.get(account_type) {
+            Some(stored) => stored.daily_balances_cutoffs.iter().copied().collect(),
+            None => vec![],
+        }
+    }
+
+    pub async fn get_bonus_grants(
+        &self,
+        account_type: &str,
+    ) -> Vec<PersistenceBonusGrantGrpcModel> {
+        let accounts = self.accounts.lock().await;
+
+        match accounts.get(account_type) {
+            Some(stored) => stored.bonus_grants.values().cloned().collect(),
+            None => vec![],
+        }
+    }
+
+    /// Stores what the event carries and rewrites the file. The event stays
+    /// applied in memory if the write fails, so the next successful write
+    /// stores it as well.
+    pub async fn apply(
+        &self,
+        account_type: &str,
+        event: &AccountPersistEvent,
+    ) -> std::io::Result<()> {
+        let account = match (&event.add_account_event, &event.update_account_event) {
+            (Some(account), _) => Some(account),
+            (None, Some(update)) => update.account_after_update.as_ref(),
+            (None, None) => None,
+        };
+
+        let mut accounts = self.accounts.lock().await;
+        let stored = accounts.entry(account_type.to_string()).or_default();
+
+        if let Some(account) = account {
+            stored
+                .accounts
+                .insert(account.id.clone(), to_persistence_model(account));
+        }
+
+        stored
+            .audit_records
+            .extend(event.audit_records.iter().map(to_persistence_audit_record));
+
+        if let Some(entry) = &event.ledger_entry {
+            stored.ledger.push(to_persistence_ledger_entry(entry));
+        }
+
+        for grant in &event.bonus_grants {
+            if is_open_bonus_grant(grant) {
+                stored
+                    .bonus_grants
+                    .insert(grant.id.clone(), to_persistence_bonus_grant(grant));
+            } else {
+                stored.bonus_grants.remove(&grant.id);
+            }
+        }
+
+        let content = serde_json::to_vec_pretty(&*accounts)?;
+        write_file(&self.path, &content).await
+    }
+
+    /// Records the cut-off once its last chunk of balances arrives. Chunks
+    /// are published in order, so the whole cut-off was published by then.
+    pub async fn apply_daily_balances(
+        &self,
+        account_type: &str,
+        event: &DailyBalancesSbEvent,
+    ) -> std::io::Result<()> {
+        if event.chunk + 1 < event.chunks_count {
+            return Ok(());
+        }
+
+        let mut accounts = self.accounts.lock().await;
+        let stored = accounts.entry(account_type.to_string()).or_default();
+        stored
+            .daily_balances_cutoffs
+            .insert(event.date_time_unix_ms);
+
+        let content = serde_json::to_vec_pretty(&*accounts)?;
+        write_file(&self.path, &content).await
+    }
+
+    /// Applies events until every sender of the bus is dropped.
+    pub async fn consume(&self, mut events: mpsc::UnboundedReceiver<LocalPersistEvent>) {
+        while let Some(event) = events.recv().await {
+            let (account_type, result) = match event {
+                LocalPersistEvent::Account {
+                    account_type,
+                    event,
+                } => {
+                    let result = self.apply(&account_type, &event).await;
+                    (account_type, result)
+                }
+                LocalPersistEvent::DailyBalances {
+                    account_type,
+                    event,
+                } => {
+                    let result = self.apply_daily_balances(&account_type, &event).await;
+                    (account_type, result)
+                }
+            };
+
+            if let Err(err) = result {
+                println!(
+                    "Can not write {} persist event to {}: {}",
+                    account_type,
+                    self.path.display(),
+                    err
+                );
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AccountsLoader for FileAccountsStore {
+    async fn load_accounts(&self, account_type: &str) -> Vec<Account> {
+        self.get_all(account_type)
+            .await
+            .into_iter()
+            .map(|x| x.into())
+            .collect()
+    }
+
+    async fn load_audit_records(&self, account_type: &str) -> Vec<AccountAuditRecord> {
+        self.get_audit_records(account_type)
+            .await
+            .into_iter()
+            .map(|x| x.into())
+            .collect()
+    }
+
+    async fn load_ledger(&self, account_type: &str, date_from: u64) -> Vec<BalanceLedgerEntry> {
+        self.get_balance_ledger(account_type, date_from)
+            .await
+            .into_iter()
+            .map(|x| x.into())
+            .collect()
+    }
+
+    async fn load_daily_balances_cutoffs(&self, account_type: &str) -> Vec<u64> {
+        self.get_daily_balances_cutoffs(account_type).await
+    }
+
+    async fn load_bonus_grants(&self, account_type: &str) -> Vec<BonusGrant> {
+        self.get_bonus_grants(account_type)
+            .await
+            .into_iter()
+            .map(|x| x.into())
+            .collect()
+    }
+}
+
+/// Writes a temporary file next to `path` and renames it over `path`, so a
+/// crash leaves either the old or the new content.
+async fn write_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut file = tokio::fs::File::create(&temp_path).await?;
+    file.write_all(content).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    tokio::fs::rename(&temp_path, path).await
+}
+
+fn to_persistence_model(account: &AccountSbModel) -> PersistenceAccountGrpcModel {
+    PersistenceAccountGrpcModel {
+        id: account.id.clone(),
+        trader_id: account.trader_id.clone(),
+        currency: account.currency.clone(),
+        balance: account.balance + account.bonus_balance + account.credit_balance,
+        create_date: account.create_date,
+        last_update_date: account.last_update_date,
+        trading_disabled: account.trading_disabled,
+        create_process_id: account.create_process_id.clone(),
+        trading_group: account.trading_group.clone(),
+        last_update_process_id: account.last_update_process_id.clone(),
+        metadata: account
+            .metadata
+            .iter()
+            .map(|x| AccountMetadataItemGrpcModel {
+                key: x.key.clone(),
+                value: x.value.clone(),
+            })
+            .collect(),
+        version: account.version,
+        bonus_balance: account.bonus_balance,
+        credit_balance: account.credit_balance,
+        trading_disabled_reasons: account
+            .trading_disabled_reasons
+            .iter()
+            .map(|x| PersistenceTradingDisabledReasonGrpcModel {
+                reason: x.reason,
+                comment: x.comment.clone(),
+                date: x.date,
+                process_id: x.process_id.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn to_persistence_audit_record(
+    record: &AccountAuditRecordSbModel,
+) -> PersistenceAuditRecordGrpcModel {
+    PersistenceAuditRecordGrpcModel {
+        account_id: record.account_id.clone(),
+        trader_id: record.trader_id.clone(),
+        field: record.field.clone(),
+        old_value: record.old_value.clone(),
+        new_value: record.new_value.clone(),
+        process_id: record.process_id.clone(),
+        operator_id: record.operator_id.clone(),
+        date: record.date_time_unix_ms,
+        operation_id: record.operation_id.clone(),
+    }
+}
+
+fn to_persistence_ledger_entry(
+    entry: &BalanceLedgerEntrySbModel,
+) -> PersistenceBalanceLedgerEntryGrpcModel {
+    PersistenceBalanceLedgerEntryGrpcModel {
+        sequence: entry.sequence,
+        account_id: entry.account_id.clone(),
+        trader_id: entry.trader_id.clone(),
+        previous_balance: entry.previous_balance,
+        delta: entry.delta,
+        new_balance: entry.new_balance,
+        reason: entry.reason,
+        comment: entry.comment.clone(),
+        reference_transaction_id: entry.reference_transaction_id.clone(),
+        process_id: entry.process_id.clone(),
+        operation_id: entry.operation_id.clone(),
+        date: entry.date_time_unix_ms,
+        cash_balance: entry.cash_balance,
+        bonus_balance: entry.bonus_balance,
+        credit_balance: entry.credit_balance,
+    }
+}
+
+fn is_open_bonus_grant(grant: &BonusGrantSbModel) -> bool {
+    grant.status == BonusGrantStatus::Active as i32
+        || grant.status == BonusGrantStatus::Earned as i32
+}
+
+fn to_persistence_bonus_grant(grant: &BonusGrantSbModel) -> PersistenceBonusGrantGrpcModel {
+    PersistenceBonusGrantGrpcModel {
+        id: grant.id.clone(),
+        account_id: grant.account_id.clone(),
+        trader_id: grant.trader_id.clone(),
+        amount: grant.amount,
+        granted_at: grant.granted_at,
+        expires_at: grant.expires_at,
+        turnover_required: grant.turnover_required,
+        turnover: grant.turnover,
+        status: grant.status,
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+
+use service_sdk::async_trait;
+use tokio::sync::RwLock;
+
+use crate::{SettingsModel, SettingsSource};
+
+/// Settings read from a JSON file, re-read on every reload. A file that can
+/// not be read or parsed on reload keeps the settings read last.
+pub struct FileSettingsSource {
+    path: PathBuf,
+    settings: RwLock<SettingsModel>,
+}
+
+impl FileSettingsSource {
+    pub async fn open(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let settings = read_settings(&path).await?;
+
+        Ok(Self {
+            path,
+            settings: RwLock::new(settings),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl SettingsSource for FileSettingsSource {
+    async fn get_settings(&self) -> SettingsModel {
+        match read_settings(&self.path).await {
+            Ok(settings) => {
+                *self.settings.write().await = settings.clone();
+                settings
+            }
+            Err(err) => {
+                println!("Keep the last settings: {}", err);
+                self.settings.read().await.clone()
+            }
+        }
+    }
+}
+
+async fn read_settings(path: &Path) -> std::io::Result<SettingsModel> {
+    let content = tokio::fs::read(path).await?;
+
+    serde_json::from_slice(&content).map_err(|err| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Can not parse {}: {}", path.display(), err),
+        )
+    })
+}
@@ -0,0 +1,78 @@
+use cfd_engine_sb_contracts::AccountPersistEvent;
+use service_sdk::async_trait;
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio::sync::mpsc;
+
+use crate::{AccountChangeSbEvent, DailyBalancesSbEvent, EventsPublisher};
+
+pub enum LocalPersistEvent {
+    Account {
+        account_type: String,
+        event: Box<AccountPersistEvent>,
+    },
+    /// The persistence records a cut-off from its daily balances.
+    DailyBalances {
+        account_type: String,
+        event: DailyBalancesSbEvent,
+    },
+}
+
+/// In-process stand-in for the service bus. Persist and daily balances
+/// events go to the receiver returned by `new`, which
+/// `FileAccountsStore::consume` reads; change events have no local
+/// subscribers and are dropped.
+pub struct LocalEventsPublisher {
+    persist_events: mpsc::UnboundedSender<LocalPersistEvent>,
+}
+
+impl LocalEventsPublisher {
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<LocalPersistEvent>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (
+            Self {
+                persist_events: sender,
+            },
+            receiver,
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsPublisher for LocalEventsPublisher {
+    async fn publish_persist_event(
+        &self,
+        account_type: &str,
+        event: &AccountPersistEvent,
+        _my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.persist_events
+            .send(LocalPersistEvent::Account {
+                account_type: account_type.to_string(),
+                event: Box::new(event.clone()),
+            })
+            .map_err(|_| "Local persistence is stopped".to_string())
+    }
+
+    async fn publish_change_event(
+        &self,
+        _account_type: &str,
+        _event: &AccountChangeSbEvent,
+        _my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn publish_daily_balances_event(
+        &self,
+        account_type: &str,
+        event: &DailyBalancesSbEvent,
+        _my_telemetry: &MyTelemetryContext,
+    ) -> Result<(), String> {
+        self.persist_events
+            .send(LocalPersistEvent::DailyBalances {
+                account_type: account_type.to_string(),
+                event: event.clone(),
+            })
+            .map_err(|_| "Local persistence is stopped".to_string())
+    }
+}
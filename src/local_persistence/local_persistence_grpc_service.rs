@@ -0,0 +1,144 @@
+use std::{pin::Pin, sync::Arc};
+
+use service_sdk::my_grpc_extensions::prelude::Stream;
+
+use crate::accounts_manager_persistence::{
+    accounts_manager_persistence_grpc_service_server::AccountsManagerPersistenceGrpcService,
+    GetAllAccountsGrpcRequest, GetBalanceLedgerGrpcRequest, PersistenceAccountGrpcModel,
+    PersistenceAuditRecordGrpcModel, PersistenceBalanceLedgerEntryGrpcModel,
+    PersistenceBonusGrantGrpcModel, PersistenceDailyBalancesCutoffGrpcModel,
+};
+
+use super::FileAccountsStore;
+
+/// Serves the persisted state from a `FileAccountsStore`, so the service can be
+/// pointed at it through `accounts_manager_persistence_grpc_url`.
+#[derive(Clone)]
+pub struct LocalPersistenceGrpcService {
+    pub store: Arc<FileAccountsStore>,
+}
+
+impl LocalPersistenceGrpcService {
+    pub fn new(store: Arc<FileAccountsStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[tonic::async_trait]
+impl AccountsManagerPersistenceGrpcService for LocalPersistenceGrpcService {
+    type GetAllAccountsStream = Pin<
+        Box<
+            dyn Stream<Item = Result<PersistenceAccountGrpcModel, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    async fn get_all_accounts(
+        &self,
+        request: tonic::Request<GetAllAccountsGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetAllAccountsStream>, tonic::Status> {
+        let request = request.into_inner();
+        let accounts = self.store.get_all(&request.accounts_type).await;
+
+        service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+            accounts.into_iter(),
+            |x| x,
+        )
+        .await
+    }
+
+    type GetAuditRecordsStream = Pin<
+        Box<
+            dyn Stream<Item = Result<PersistenceAuditRecordGrpcModel, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    async fn get_audit_records(
+        &self,
+        request: tonic::Request<GetAllAccountsGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetAuditRecordsStream>, tonic::Status> {
+        let request = request.into_inner();
+        let records = self.store.get_audit_records(&request.accounts_type).await;
+
+        service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(records.into_iter(), |x| x)
+            .await
+    }
+
+    type GetBalanceLedgerStream = Pin<
+        Box<
+            dyn Stream<Item = Result<PersistenceBalanceLedgerEntryGrpcModel, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    async fn get_balance_ledger(
+        &self,
+        request: tonic::Request<GetBalanceLedgerGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetBalanceLedgerStream>, tonic::Status> {
+        let request = request.into_inner();
+        let entries = self
+            .store
+            .get_balance_ledger(&request.accounts_type, request.date_from)
+            .await;
+
+        service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(entries.into_iter(), |x| x)
+            .await
+    }
+
+    type GetDailyBalancesCutoffsStream = Pin<
+        Box<
+            dyn Stream<Item = Result<PersistenceDailyBalancesCutoffGrpcModel, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    async fn get_daily_balances_cutoffs(
+        &self,
+        request: tonic::Request<GetAllAccountsGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetDailyBalancesCutoffsStream>, tonic::Status> {
+        let request = request.into_inner();
+        let cutoffs = self
+            .store
+            .get_daily_balances_cutoffs(&request.accounts_type)
+            .await;
+
+        service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(
+            cutoffs.into_iter(),
+            |date| PersistenceDailyBalancesCutoffGrpcModel { date },
+        )
+        .await
+    }
+
+    type GetBonusGrantsStream = Pin<
+        Box<
+            dyn Stream<Item = Result<PersistenceBonusGrantGrpcModel, tonic::Status>>
+                + Send
+                + Sync
+                + 'static,
+        >,
+    >;
+
+    async fn get_bonus_grants(
+        &self,
+        request: tonic::Request<GetAllAccountsGrpcRequest>,
+    ) -> Result<tonic::Response<Self::GetBonusGrantsStream>, tonic::Status> {
+        let request = request.into_inner();
+        let grants = self.store.get_bonus_grants(&request.accounts_type).await;
+
+        service_sdk::my_grpc_extensions::grpc_server::send_vec_to_stream(grants.into_iter(), |x| x)
+            .await
+    }
+
+    async fn ping(&self, _: tonic::Request<()>) -> Result<tonic::Response<()>, tonic::Status> {
+        Ok(tonic::Response::new(()))
+    }
+}
@@ -0,0 +1,9 @@
+mod file_accounts_store;
+mod file_settings_source;
+mod local_events_publisher;
+mod local_persistence_grpc_service;
+
+pub use file_accounts_store::*;
+pub use file_settings_source::*;
+pub use local_events_publisher::*;
+pub use local_persistence_grpc_service::*;
@@ -1,8 +1,10 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use accounts_manager::{
     accounts_manager::accounts_manager_grpc_service_server::AccountsManagerGrpcServiceServer,
-    AppContext, GrpcService, SettingsReader
+    AppContext, BonusExpiryJob, EodSnapshotJob, GrpcService, LedgerCheckJob, LedgerRetentionJob,
+    PersistenceAccountsLoader, SbEventsOutboxJob, SbEventsPublisher, SettingsReader,
+    SettingsReloadJob,
 };
 use service_sdk::ServiceInfo;
 
@@ -13,7 +15,14 @@ async fn main() {
 
     let mut service_context = service_sdk::ServiceContext::new(settings_reader.clone()).await;
 
-    let app_context = Arc::new(AppContext::new(settings_reader.clone(), &service_context).await);
+    let app_context = Arc::new(
+        AppContext::new(
+            settings_reader.clone(),
+            &PersistenceAccountsLoader::new(settings_reader.clone()),
+            Arc::new(SbEventsPublisher::new(&service_context).await),
+        )
+        .await,
+    );
 
     service_context.configure_grpc_server(|config| {
         config.add_grpc_service(AccountsManagerGrpcServiceServer::new(GrpcService::new(
@@ -21,8 +30,54 @@ async fn main() {
         )))
     });
 
-    trade_log::core::TRADE_LOG.init_component_name(settings_reader.get_service_name().as_str()).await;
-    trade_log::core::TRADE_LOG.start(&service_context.sb_client).await;
+    service_context.register_timer(Duration::from_secs(10), |timer| {
+        timer.register_timer(
+            "SettingsReload",
+            Arc::new(SettingsReloadJob::new(app_context.clone())),
+        )
+    });
+
+    service_context.register_timer(Duration::from_secs(1), |timer| {
+        timer.register_timer(
+            "SbEventsOutbox",
+            Arc::new(SbEventsOutboxJob::new(app_context.clone())),
+        )
+    });
+
+    service_context.register_timer(Duration::from_secs(60), |timer| {
+        timer.register_timer(
+            "LedgerCheck",
+            Arc::new(LedgerCheckJob::new(app_context.clone())),
+        )
+    });
+
+    service_context.register_timer(Duration::from_secs(60), |timer| {
+        timer.register_timer(
+            "EodSnapshot",
+            Arc::new(EodSnapshotJob::new(app_context.clone())),
+        )
+    });
+
+    service_context.register_timer(Duration::from_secs(60), |timer| {
+        timer.register_timer(
+            "BonusExpiry",
+            Arc::new(BonusExpiryJob::new(app_context.clone())),
+        )
+    });
+
+    service_context.register_timer(Duration::from_secs(60 * 60), |timer| {
+        timer.register_timer(
+            "LedgerRetention",
+            Arc::new(LedgerRetentionJob::new(app_context.clone())),
+        )
+    });
+
+    trade_log::core::TRADE_LOG
+        .init_component_name(settings_reader.get_service_name().as_str())
+        .await;
+    trade_log::core::TRADE_LOG
+        .start(&service_context.sb_client)
+        .await;
 
     service_context.start_application().await;
 }
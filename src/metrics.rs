@@ -0,0 +1,52 @@
+use std::time::Instant;
+
+use crate::accounts_manager::UpdateBalanceReason;
+
+pub fn observe_rpc(method: &'static str, result: &'static str, started: Instant) {
+    service_sdk::metrics::histogram!("grpc_request_duration_seconds", "method" => method)
+        .record(started.elapsed().as_secs_f64());
+    service_sdk::metrics::counter!("grpc_requests_total", "method" => method, "result" => result)
+        .increment(1);
+}
+
+/// Volumes are recorded as absolute values split by direction, so the sums of
+/// deposits and withdrawals do not cancel each other out.
+pub fn observe_balance_update(reason: UpdateBalanceReason, currency: &str, delta: f64) {
+    let direction = if delta >= 0.0 { "credit" } else { "debit" };
+
+    service_sdk::metrics::counter!(
+        "balance_updates_total",
+        "reason" => reason.as_str_name(),
+        "currency" => currency.to_string()
+    )
+    .increment(1);
+    service_sdk::metrics::histogram!(
+        "balance_update_volume",
+        "reason" => reason.as_str_name(),
+        "currency" => currency.to_string(),
+        "direction" => direction
+    )
+    .record(delta.abs());
+}
+
+pub fn observe_accounts_cache_lock_wait(mode: &'static str, started: Instant) {
+    service_sdk::metrics::histogram!("accounts_cache_lock_wait_seconds", "mode" => mode)
+        .record(started.elapsed().as_secs_f64());
+}
+
+pub fn set_process_id_cache_size(size: usize) {
+    service_sdk::metrics::gauge!("process_id_cache_size").set(size as f64);
+}
+
+pub fn set_sb_events_outbox_size(size: usize) {
+    service_sdk::metrics::gauge!("sb_events_outbox_size").set(size as f64);
+}
+
+pub fn observe_sb_publish(event: &'static str, started: Instant, success: bool) {
+    service_sdk::metrics::histogram!("sb_publish_duration_seconds", "event" => event)
+        .record(started.elapsed().as_secs_f64());
+
+    if !success {
+        service_sdk::metrics::counter!("sb_publish_failures_total", "event" => event).increment(1);
+    }
+}
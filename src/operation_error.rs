@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::accounts_manager::AccountsManagerOperationResult;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OperationError {
+    TraderNotFound,
+    AccountNofFound,
+    NotEnoughBalance,
+    ProcessIdDuplicate,
+    InvalidRequest(String),
+    Unavailable(String),
+    Conflict(String),
+    Internal(String),
+}
+
+impl OperationError {
+    pub fn as_operation_result(&self) -> AccountsManagerOperationResult {
+        match self {
+            OperationError::TraderNotFound => AccountsManagerOperationResult::TraderNotFound,
+            OperationError::AccountNofFound => AccountsManagerOperationResult::AccountNotFound,
+            OperationError::NotEnoughBalance => AccountsManagerOperationResult::NotEnoughBalance,
+            OperationError::ProcessIdDuplicate => {
+                AccountsManagerOperationResult::ProcessIdDuplicate
+            }
+            OperationError::InvalidRequest(_) => AccountsManagerOperationResult::InvalidRequest,
+            OperationError::Unavailable(_) => AccountsManagerOperationResult::ServiceUnavailable,
+            OperationError::Conflict(_) => AccountsManagerOperationResult::Conflict,
+            OperationError::Internal(_) => AccountsManagerOperationResult::InternalError,
+        }
+    }
+
+    pub fn as_grpc_error(&self) -> i32 {
+        self.as_operation_result() as i32
+    }
+
+    pub fn get_message(&self) -> String {
+        match self {
+            OperationError::TraderNotFound => "Trader not found".to_string(),
+            OperationError::AccountNofFound => "Account not found".to_string(),
+            OperationError::NotEnoughBalance => "Not enough balance".to_string(),
+            OperationError::ProcessIdDuplicate => {
+                "Request with the same process id was already processed".to_string()
+            }
+            OperationError::InvalidRequest(message)
+            | OperationError::Unavailable(message)
+            | OperationError::Conflict(message)
+            | OperationError::Internal(message) => message.clone(),
+        }
+    }
+
+    fn get_status_code(&self) -> tonic::Code {
+        match self {
+            OperationError::TraderNotFound => tonic::Code::NotFound,
+            OperationError::AccountNofFound => tonic::Code::NotFound,
+            OperationError::NotEnoughBalance => tonic::Code::FailedPrecondition,
+            OperationError::ProcessIdDuplicate => tonic::Code::AlreadyExists,
+            OperationError::InvalidRequest(_) => tonic::Code::InvalidArgument,
+            OperationError::Unavailable(_) => tonic::Code::Unavailable,
+            OperationError::Conflict(_) => tonic::Code::Aborted,
+            OperationError::Internal(_) => tonic::Code::Internal,
+        }
+    }
+}
+
+impl From<OperationError> for tonic::Status {
+    fn from(error: OperationError) -> Self {
+        // Details carry the result code name so clients can map the status
+        // back to `AccountsManagerOperationResult` without parsing the message.
+        let details = error
+            .as_operation_result()
+            .as_str_name()
+            .as_bytes()
+            .to_vec();
+
+        tonic::Status::with_details(error.get_status_code(), error.get_message(), details.into())
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accounts_manager::{AccountMetadataItemGrpcModel, TradingDisabledReason},
+    AccountChange,
+};
+
+service_sdk::macros::use_my_sb_entity_protobuf_model!();
+
+/// Non-balance change of an account. Exactly one of the `*_changed` fields is
+/// set and carries both the old and the new value.
+#[derive(Clone, PartialEq, ::prost::Message, Serialize, Deserialize)]
+#[my_sb_entity_protobuf_model(topic_id = "account-change-events")]
+pub struct AccountChangeSbEvent {
+    #[prost(string, tag = "1")]
+    pub account_id: String,
+    #[prost(string, tag = "2")]
+    pub trader_id: String,
+    #[prost(string, tag = "3")]
+    pub process_id: String,
+    #[prost(uint64, tag = "4")]
+    pub date_time_unix_ms: u64,
+    #[prost(message, optional, tag = "5")]
+    pub trading_group_changed: Option<AccountTradingGroupChangedSbModel>,
+    #[prost(message, optional, tag = "6")]
+    pub trading_disabled_changed: Option<AccountTradingDisabledChangedSbModel>,
+    #[prost(message, optional, tag = "7")]
+    pub metadata_changed: Option<AccountMetadataChangedSbModel>,
+    #[prost(string, tag = "8")]
+    pub operation_id: String,
+    /// Account version after the change.
+    #[prost(uint64, tag = "9")]
+    pub version: u64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize, Deserialize)]
+pub struct AccountTradingGroupChangedSbModel {
+    #[prost(string, tag = "1")]
+    pub old_trading_group: String,
+    #[prost(string, tag = "2")]
+    pub new_trading_group: String,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize, Deserialize)]
+pub struct AccountTradingDisabledChangedSbModel {
+    #[prost(bool, tag = "1")]
+    pub old_trading_disabled: bool,
+    #[prost(bool, tag = "2")]
+    pub new_trading_disabled: bool,
+    #[prost(int32, tag = "3")]
+    pub reason: i32,
+    #[prost(string, optional, tag = "4")]
+    pub comment: Option<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize, Deserialize)]
+pub struct AccountMetadataChangedSbModel {
+    #[prost(message, repeated, tag = "1")]
+    pub old_metadata: Vec<AccountChangeMetadataSbModel>,
+    #[prost(message, repeated, tag = "2")]
+    pub new_metadata: Vec<AccountChangeMetadataSbModel>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize, Deserialize)]
+pub struct AccountChangeMetadataSbModel {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+impl AccountChangeSbEvent {
+    fn new(change: &AccountChange, operation_id: &str) -> Self {
+        Self {
+            account_id: change.after.id.clone(),
+            trader_id: change.after.trader_id.clone(),
+            process_id: change.after.last_update_process_id.clone(),
+            date_time_unix_ms: change.after.last_update_date,
+            trading_group_changed: None,
+            trading_disabled_changed: None,
+            metadata_changed: None,
+            operation_id: operation_id.to_string(),
+            version: change.after.version,
+        }
+    }
+
+    pub fn trading_group_changed(change: &AccountChange, operation_id: &str) -> Self {
+        Self {
+            trading_group_changed: Some(AccountTradingGroupChangedSbModel {
+                old_trading_group: change.before.trading_group.clone(),
+                new_trading_group: change.after.trading_group.clone(),
+            }),
+            ..Self::new(change, operation_id)
+        }
+    }
+
+    pub fn trading_disabled_changed(
+        change: &AccountChange,
+        operation_id: &str,
+        reason: TradingDisabledReason,
+        comment: Option<String>,
+    ) -> Self {
+        Self {
+            trading_disabled_changed: Some(AccountTradingDisabledChangedSbModel {
+                old_trading_disabled: change.before.trading_disabled,
+                new_trading_disabled: change.after.trading_disabled,
+                reason: reason as i32,
+                comment,
+            }),
+            ..Self::new(change, operation_id)
+        }
+    }
+
+    pub fn metadata_changed(change: &AccountChange, operation_id: &str) -> Self {
+        Self {
+            metadata_changed: Some(AccountMetadataChangedSbModel {
+                old_metadata: map_metadata(&change.before.metadata),
+                new_metadata: map_metadata(&change.after.metadata),
+            }),
+            ..Self::new(change, operation_id)
+        }
+    }
+}
+
+fn map_metadata(metadata: &[AccountMetadataItemGrpcModel]) -> Vec<AccountChangeMetadataSbModel> {
+    metadata
+        .iter()
+        .map(|x| AccountChangeMetadataSbModel {
+            key: x.key.clone(),
+            value: x.value.clone(),
+        })
+        .collect()
+}
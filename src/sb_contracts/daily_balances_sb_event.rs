@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::DailyBalance;
+
+service_sdk::macros::use_my_sb_entity_protobuf_model!();
+
+/// Closing balances of the accounts of one type at one cut-off. The balances
+/// of a cut-off are split over `chunks_count` events.
+#[derive(Clone, PartialEq, ::prost::Message, Serialize, Deserialize)]
+#[my_sb_entity_protobuf_model(topic_id = "account-daily-balances")]
+pub struct DailyBalancesSbEvent {
+    #[prost(uint64, tag = "1")]
+    pub date_time_unix_ms: u64,
+    #[prost(message, repeated, tag = "2")]
+    pub balances: Vec<DailyBalanceSbModel>,
+    /// Zero-based position of the event among the ones of the cut-off.
+    #[prost(uint32, tag = "3")]
+    pub chunk: u32,
+    #[prost(uint32, tag = "4")]
+    pub chunks_count: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message, Serialize, Deserialize)]
+pub struct DailyBalanceSbModel {
+    #[prost(string, tag = "1")]
+    pub account_id: String,
+    #[prost(string, tag = "2")]
+    pub trader_id: String,
+    #[prost(string, tag = "3")]
+    pub currency: String,
+    #[prost(double, tag = "4")]
+    pub balance: f64,
+}
+
+impl Into<DailyBalanceSbModel> for DailyBalance {
+    fn into(self) -> DailyBalanceSbModel {
+        DailyBalanceSbModel {
+            account_id: self.account_id,
+            trader_id: self.trader_id,
+            currency: self.currency,
+            balance: self.balance,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod account_change_sb_event;
+mod daily_balances_sb_event;
+
+pub use account_change_sb_event::*;
+pub use daily_balances_sb_event::*;
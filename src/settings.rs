@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
 use service_sdk::async_trait;
 
@@ -18,29 +20,91 @@ pub struct SettingsModel {
     pub default_account_trading_group: String,
     pub accounts_manager_persistence_grpc_url: String,
     pub accounts_default_currency: Option<String>,
+    pub default_accounts: Option<HashMap<String, Vec<DefaultAccountSettingsModel>>>,
+    pub rate_limits: Option<RateLimitsSettingsModel>,
+    pub auth: Option<AuthSettingsModel>,
+    /// Account types hosted by this instance. `_type` is always hosted and is
+    /// used for requests that do not specify a type.
+    pub account_types: Option<Vec<String>>,
+    /// How long balance operations are kept for point-in-time queries.
+    /// Defaults to `DEFAULT_BALANCE_HISTORY_RETENTION_DAYS`.
+    pub balance_history_retention_days: Option<u32>,
+    /// UTC time of day, `HH:MM`, daily closing balances are taken at.
+    /// Defaults to `DEFAULT_EOD_CUTOFF_UTC`.
+    pub eod_cutoff_utc: Option<String>,
+    /// Balance buckets a trading loss is taken from, first to last: `cash`,
+    /// `bonus`, `credit`. Defaults to all three in that order.
+    pub trading_loss_buckets: Option<Vec<String>>,
+    /// How long a bonus stays unless the request sets its expiry.
+    /// Defaults to `DEFAULT_BONUS_EXPIRATION_DAYS`.
+    pub bonus_expiration_days: Option<u32>,
+    /// History loaded from the persistence service on startup. Missing loads
+    /// nothing, see `PersistenceLoadsSettingsModel`.
+    pub persistence_loads: Option<PersistenceLoadsSettingsModel>,
     pub my_telemetry: String,
     pub seq_conn_string: String,
     pub _type: String,
 }
 
-impl SettingsReader {
-    pub async fn get_default_account_balance_and_group(&self) -> (f64, String) {
-        let read_access = self.settings.read().await;
-        return (
-            read_access.default_account_balance,
-            read_access.default_account_trading_group.to_string(),
-        );
-    }
+pub const DEFAULT_BALANCE_HISTORY_RETENTION_DAYS: u32 = 400;
+pub const DEFAULT_EOD_CUTOFF_UTC: &str = "00:00";
+pub const DEFAULT_BONUS_EXPIRATION_DAYS: u32 = 30;
 
-    pub async fn get_accounts_default_currency(&self) -> Option<String> {
-        let read_access = self.settings.read().await;
-        return read_access.accounts_default_currency.clone();
-    }
+/// Template of an account every new trader gets. Missing balance and group
+/// fall back to `default_account_balance` and `default_account_trading_group`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DefaultAccountSettingsModel {
+    pub currency: String,
+    pub balance: Option<f64>,
+    pub trading_group: Option<String>,
+    pub metadata: Option<BTreeMap<String, String>>,
+}
 
-    pub async fn get_env_type(&self) -> String {
-        let read_access = self.get_settings().await;
-        return read_access._type.clone();
-    }
+/// Each load calls an RPC older persistence services do not have, so the
+/// persistence service is deployed first and the load is turned on after it.
+/// A load left off starts that history empty: ledger entries, audit records,
+/// published cut-offs and bonus grants are then only those made since start.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct PersistenceLoadsSettingsModel {
+    #[serde(default)]
+    pub audit_records: bool,
+    #[serde(default)]
+    pub balance_ledger: bool,
+    #[serde(default)]
+    pub daily_balances_cutoffs: bool,
+    #[serde(default)]
+    pub bonus_grants: bool,
+}
+
+/// Limits are optional one by one; a missing one means no limit of that kind.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RateLimitsSettingsModel {
+    pub per_trader: Option<RateLimitSettingsModel>,
+    pub per_caller: Option<RateLimitSettingsModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct RateLimitSettingsModel {
+    pub requests_per_second: f64,
+    pub burst: f64,
+}
+
+/// When set, every call must carry one of the `api_keys` and is limited to
+/// what the `permissions` of the key owner allow.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuthSettingsModel {
+    /// Service name -> api key.
+    pub api_keys: HashMap<String, String>,
+    /// Service name -> permissions. A service without an entry can call nothing.
+    pub permissions: HashMap<String, ServicePermissionsSettingsModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServicePermissionsSettingsModel {
+    /// RPC names as in the proto, or `*` for all of them.
+    pub methods: Vec<String>,
+    /// `UpdateBalanceReason` names the service may apply. Missing means all.
+    pub balance_reasons: Option<Vec<String>>,
 }
 
 #[async_trait::async_trait]
@@ -0,0 +1,446 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use chrono::Timelike;
+use serde::Serialize;
+
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::{
+    AuthSettingsModel, BalanceBucket, BalanceRules, DefaultAccountSettingsModel,
+    RateLimitSettingsModel, RateLimitsSettingsModel, SettingsModel, SettingsSource,
+    DEFAULT_BALANCE_HISTORY_RETENTION_DAYS, DEFAULT_BONUS_EXPIRATION_DAYS, DEFAULT_EOD_CUTOFF_UTC,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingsChange {
+    pub field: &'static str,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+pub enum SettingsReloadResult {
+    Unchanged,
+    Applied(Vec<SettingsChange>),
+    Rejected(Vec<String>),
+}
+
+/// Last valid snapshot of the settings. Business code reads settings from
+/// here instead of the `SettingsSource`, so a broken reload never reaches it.
+pub struct ValidatedSettings {
+    settings_source: Arc<dyn SettingsSource>,
+    current: RwLock<SettingsModel>,
+    /// Errors of the last rejected reload, so the same broken settings are
+    /// logged once rather than on every reload.
+    last_rejection: Mutex<Option<Vec<String>>>,
+}
+
+impl ValidatedSettings {
+    pub async fn new(settings_source: Arc<dyn SettingsSource>) -> Result<Self, Vec<String>> {
+        let settings = settings_source.get_settings().await;
+        validate_settings(&settings)?;
+        set_settings_metrics(&settings);
+
+        Ok(Self {
+            settings_source,
+            current: RwLock::new(settings),
+            last_rejection: Mutex::new(None),
+        })
+    }
+
+    pub async fn reload(&self) -> SettingsReloadResult {
+        let settings = self.settings_source.get_settings().await;
+
+        if let Err(errors) = validate_settings(&settings) {
+            service_sdk::metrics::counter!("settings_reload_total", "result" => "rejected")
+                .increment(1);
+
+            let mut last_rejection = self.last_rejection.lock().await;
+
+            if last_rejection.as_ref() != Some(&errors) {
+                trade_log::trade_log!(
+                    "",
+                    "",
+                    "settings-reload",
+                    "",
+                    "Settings reload rejected, keeping the last valid settings.",
+                    MyTelemetryContext::new(),
+                    "errors" = &errors
+                );
+                *last_rejection = Some(errors.clone());
+            }
+
+            return SettingsReloadResult::Rejected(errors);
+        }
+
+        *self.last_rejection.lock().await = None;
+
+        let mut current = self.current.write().await;
+        let changes = get_settings_changes(&current, &settings);
+        set_settings_metrics(&settings);
+        *current = settings;
+
+        if changes.is_empty() {
+            return SettingsReloadResult::Unchanged;
+        }
+
+        service_sdk::metrics::counter!("settings_reload_total", "result" => "applied").increment(1);
+
+        for change in &changes {
+            service_sdk::metrics::counter!("settings_changes_total", "field" => change.field)
+                .increment(1);
+        }
+
+        trade_log::trade_log!(
+            "",
+            "",
+            "settings-reload",
+            "",
+            "Settings reloaded.",
+            MyTelemetryContext::new(),
+            "changes" = &changes
+        );
+
+        SettingsReloadResult::Applied(changes)
+    }
+
+    pub async fn get_default_account_balance_and_group(&self) -> (f64, String) {
+        let read_access = self.current.read().await;
+        return (
+            read_access.default_account_balance,
+            read_access.default_account_trading_group.to_string(),
+        );
+    }
+
+    /// Falls back to the legacy `accounts_default_currency` when no list is
+    /// configured for the type.
+    pub async fn get_default_accounts(
+        &self,
+        account_type: &str,
+    ) -> Vec<DefaultAccountSettingsModel> {
+        let read_access = self.current.read().await;
+        get_default_accounts(&read_access, account_type)
+    }
+
+    pub async fn get_rate_limits(&self) -> RateLimitsSettingsModel {
+        let read_access = self.current.read().await;
+        return read_access.rate_limits.clone().unwrap_or_default();
+    }
+
+    pub async fn get_auth(&self) -> Option<AuthSettingsModel> {
+        let read_access = self.current.read().await;
+        return read_access.auth.clone();
+    }
+
+    pub async fn get_env_type(&self) -> String {
+        let read_access = self.current.read().await;
+        return read_access._type.clone();
+    }
+
+    pub async fn get_balance_history_retention(&self) -> Duration {
+        let read_access = self.current.read().await;
+        let days = read_access
+            .balance_history_retention_days
+            .unwrap_or(DEFAULT_BALANCE_HISTORY_RETENTION_DAYS);
+
+        Duration::from_secs(days as u64 * 24 * 60 * 60)
+    }
+
+    pub async fn get_bonus_expiration(&self) -> Duration {
+        let read_access = self.current.read().await;
+        let days = read_access
+            .bonus_expiration_days
+            .unwrap_or(DEFAULT_BONUS_EXPIRATION_DAYS);
+
+        Duration::from_secs(days as u64 * 24 * 60 * 60)
+    }
+
+    /// Offset of the end-of-day cut-off from the UTC midnight.
+    pub async fn get_eod_cutoff(&self) -> Duration {
+        let read_access = self.current.read().await;
+        let cutoff = read_access
+            .eod_cutoff_utc
+            .as_deref()
+            .unwrap_or(DEFAULT_EOD_CUTOFF_UTC);
+
+        parse_eod_cutoff(cutoff).unwrap()
+    }
+
+    pub async fn get_balance_rules(&self) -> BalanceRules {
+        let read_access = self.current.read().await;
+
+        match &read_access.trading_loss_buckets {
+            Some(buckets) => BalanceRules {
+                trading_loss_order: buckets
+                    .iter()
+                    .map(|x| BalanceBucket::parse(x).unwrap())
+                    .collect(),
+            },
+            None => BalanceRules::default(),
+        }
+    }
+
+    /// Read once at startup: caches are created per type, so a changed list
+    /// takes effect after a restart.
+    pub async fn get_account_types(&self) -> Vec<String> {
+        let read_access = self.current.read().await;
+        get_account_types(&read_access)
+    }
+}
+
+fn parse_eod_cutoff(value: &str) -> Option<Duration> {
+    let time = chrono::NaiveTime::parse_from_str(value, "%H:%M").ok()?;
+    Some(Duration::from_secs(time.num_seconds_from_midnight() as u64))
+}
+
+fn set_settings_metrics(settings: &SettingsModel) {
+    service_sdk::metrics::gauge!("settings_default_account_balance")
+        .set(settings.default_account_balance);
+}
+
+fn get_account_types(settings: &SettingsModel) -> Vec<String> {
+    let mut result = vec![settings._type.clone()];
+
+    for account_type in settings.account_types.iter().flatten() {
+        if !result.contains(account_type) {
+            result.push(account_type.clone());
+        }
+    }
+
+    result
+}
+
+fn get_default_accounts(
+    settings: &SettingsModel,
+    account_type: &str,
+) -> Vec<DefaultAccountSettingsModel> {
+    if let Some(default_accounts) = &settings.default_accounts {
+        if let Some(accounts) = default_accounts.get(account_type) {
+            return accounts.clone();
+        }
+    }
+
+    match &settings.accounts_default_currency {
+        Some(currency) => vec![DefaultAccountSettingsModel {
+            currency: currency.clone(),
+            balance: None,
+            trading_group: None,
+            metadata: None,
+        }],
+        None => vec![],
+    }
+}
+
+pub fn validate_settings(settings: &SettingsModel) -> Result<(), Vec<String>> {
+    let mut errors = vec![];
+
+    validate_balance(
+        &mut errors,
+        "default_account_balance",
+        settings.default_account_balance,
+    );
+    validate_not_empty(
+        &mut errors,
+        "default_account_trading_group",
+        &settings.default_account_trading_group,
+    );
+
+    if let Some(currency) = &settings.accounts_default_currency {
+        validate_not_empty(&mut errors, "accounts_default_currency", currency);
+    }
+
+    for (env_type, accounts) in settings.default_accounts.iter().flatten() {
+        // A trader has at most one default account per currency, see
+        // `AccountsStore::add_missing_accounts`.
+        let mut currencies = HashSet::new();
+
+        for account in accounts {
+            let field = format!("default_accounts.{}", env_type);
+
+            validate_not_empty(
+                &mut errors,
+                &format!("{}.currency", field),
+                &account.currency,
+            );
+
+            if !currencies.insert(account.currency.as_str()) {
+                errors.push(format!(
+                    "{}.currency {} is listed more than once",
+                    field, account.currency
+                ));
+            }
+
+            if let Some(balance) = account.balance {
+                validate_balance(&mut errors, &format!("{}.balance", field), balance);
+            }
+
+            if let Some(trading_group) = &account.trading_group {
+                validate_not_empty(
+                    &mut errors,
+                    &format!("{}.trading_group", field),
+                    trading_group,
+                );
+            }
+        }
+    }
+
+    validate_not_empty(&mut errors, "_type", &settings._type);
+
+    for account_type in settings.account_types.iter().flatten() {
+        validate_not_empty(&mut errors, "account_types", account_type);
+    }
+
+    if settings.balance_history_retention_days == Some(0) {
+        errors.push("balance_history_retention_days must be positive".to_string());
+    }
+
+    if settings.bonus_expiration_days == Some(0) {
+        errors.push("bonus_expiration_days must be positive".to_string());
+    }
+
+    let mut trading_loss_buckets = vec![];
+
+    for name in settings.trading_loss_buckets.iter().flatten() {
+        match BalanceBucket::parse(name) {
+            Some(bucket) if trading_loss_buckets.contains(&bucket) => errors.push(format!(
+                "trading_loss_buckets lists {} more than once",
+                name
+            )),
+            Some(bucket) => trading_loss_buckets.push(bucket),
+            None => errors.push(format!(
+                "trading_loss_buckets has unknown bucket '{}', expected cash, bonus or credit",
+                name
+            )),
+        }
+    }
+
+    if let Some(cutoff) = &settings.eod_cutoff_utc {
+        if parse_eod_cutoff(cutoff).is_none() {
+            errors.push(format!("eod_cutoff_utc must be HH:MM, got '{}'", cutoff));
+        }
+    }
+
+    if let Some(rate_limits) = &settings.rate_limits {
+        validate_rate_limit(
+            &mut errors,
+            "rate_limits.per_trader",
+            &rate_limits.per_trader,
+        );
+        validate_rate_limit(
+            &mut errors,
+            "rate_limits.per_caller",
+            &rate_limits.per_caller,
+        );
+    }
+
+    if let Some(auth) = &settings.auth {
+        for (service_name, api_key) in &auth.api_keys {
+            validate_not_empty(
+                &mut errors,
+                &format!("auth.api_keys.{}", service_name),
+                api_key,
+            );
+
+            if auth
+                .api_keys
+                .iter()
+                .any(|(other, key)| other != service_name && key == api_key)
+            {
+                errors.push(format!(
+                    "auth.api_keys.{} is shared with another service",
+                    service_name
+                ));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(())
+}
+
+/// Differences in the settings that change what new accounts look like.
+pub fn get_settings_changes(old: &SettingsModel, new: &SettingsModel) -> Vec<SettingsChange> {
+    let mut result = vec![];
+
+    let mut add = |field: &'static str, old_value: String, new_value: String| {
+        if old_value != new_value {
+            result.push(SettingsChange {
+                field,
+                old_value,
+                new_value,
+            });
+        }
+    };
+
+    add(
+        "default_account_balance",
+        old.default_account_balance.to_string(),
+        new.default_account_balance.to_string(),
+    );
+    add(
+        "default_account_trading_group",
+        old.default_account_trading_group.clone(),
+        new.default_account_trading_group.clone(),
+    );
+
+    for account_type in get_account_types(new) {
+        add(
+            "default_accounts",
+            format_default_accounts(&get_default_accounts(old, &account_type)),
+            format_default_accounts(&get_default_accounts(new, &account_type)),
+        );
+    }
+
+    result
+}
+
+fn format_default_accounts(accounts: &[DefaultAccountSettingsModel]) -> String {
+    accounts
+        .iter()
+        .map(|x| {
+            format!(
+                "{}:{}:{}",
+                x.currency,
+                x.balance.map(|x| x.to_string()).unwrap_or_default(),
+                x.trading_group.clone().unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn validate_balance(errors: &mut Vec<String>, field: &str, value: f64) {
+    if !value.is_finite() || value < 0.0 {
+        errors.push(format!(
+            "{} must be a non-negative number, got {}",
+            field, value
+        ));
+    }
+}
+
+fn validate_not_empty(errors: &mut Vec<String>, field: &str, value: &str) {
+    if value.trim().is_empty() {
+        errors.push(format!("{} must not be empty", field));
+    }
+}
+
+fn validate_rate_limit(
+    errors: &mut Vec<String>,
+    field: &str,
+    limit: &Option<RateLimitSettingsModel>,
+) {
+    let Some(limit) = limit else {
+        return;
+    };
+
+    if !limit.requests_per_second.is_finite() || limit.requests_per_second <= 0.0 {
+        errors.push(format!("{}.requests_per_second must be positive", field));
+    }
+
+    if !limit.burst.is_finite() || limit.burst < 1.0 {
+        errors.push(format!("{}.burst must be at least 1", field));
+    }
+}
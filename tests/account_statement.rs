@@ -0,0 +1,139 @@
+mod common;
+
+use accounts_manager::accounts_manager::{
+    AccountManagerUpdateAccountBalanceGrpcRequest, AccountStatementRowType, UpdateBalanceReason,
+};
+use accounts_manager::{Account, AccountStatement, BalanceLedger, OperationError};
+use common::{update_balance_request, AccountBuilder};
+
+fn account() -> Account {
+    AccountBuilder::new("account").created_at(1_000).build()
+}
+
+fn ledger() -> BalanceLedger {
+    let mut ledger = BalanceLedger::new();
+    let mut snapshot = account();
+    ledger.open(&snapshot);
+
+    for (date, delta, reason, comment) in [
+        (2_000, 50.0, UpdateBalanceReason::Deposit, "card, visa"),
+        (3_000, -20.0, UpdateBalanceReason::Withdrawal, "wire \"A\""),
+        (4_000, 10.0, UpdateBalanceReason::TradingResult, "trade"),
+    ] {
+        snapshot.balance += delta;
+        snapshot.last_update_date = date;
+        snapshot.last_update_process_id = format!("process-{}", date);
+
+        ledger.append(
+            &snapshot,
+            &AccountManagerUpdateAccountBalanceGrpcRequest {
+                comment: comment.to_string(),
+                reference_transaction_id: Some(format!("ref-{}", date)),
+                ..update_balance_request(delta, reason, &snapshot.last_update_process_id)
+            },
+            &format!("op-{}", date),
+        );
+    }
+
+    ledger
+}
+
+#[test]
+fn statement_covers_the_period() {
+    let statement = AccountStatement::new(&account(), &ledger(), 1_500, 3_500).unwrap();
+
+    assert_eq!(statement.opening_balance, 100.0);
+    assert_eq!(statement.closing_balance, 130.0);
+    assert_eq!(statement.operations.len(), 2);
+    assert_eq!(
+        statement.operations[0].reason,
+        Some(UpdateBalanceReason::Deposit)
+    );
+    assert_eq!(
+        statement.operations[1].reference_transaction_id.as_deref(),
+        Some("ref-3000")
+    );
+
+    let rows = statement.rows();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0].row_type, AccountStatementRowType::Opening);
+    assert_eq!(rows[3].row_type, AccountStatementRowType::Closing);
+    assert_eq!(rows[3].balance, 130.0);
+
+    // The account did not exist before its opening entry.
+    let statement = AccountStatement::new(&account(), &ledger(), 0, 500).unwrap();
+    assert_eq!(statement.opening_balance, 0.0);
+    assert_eq!(statement.closing_balance, 0.0);
+    assert!(statement.operations.is_empty());
+
+    // The opening entry is the opening balance, not an operation.
+    let statement = AccountStatement::new(&account(), &ledger(), 0, 2_500).unwrap();
+    assert_eq!(statement.opening_balance, 100.0);
+    assert_eq!(statement.closing_balance, 150.0);
+    assert_eq!(statement.operations.len(), 1);
+    assert_eq!(statement.rows().len(), 3);
+
+    let mut pruned = ledger();
+    pruned.prune(3_500);
+    assert!(matches!(
+        AccountStatement::new(&account(), &pruned, 1_500, 3_500),
+        Err(OperationError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn statement_is_exported_as_csv_and_json() {
+    let statement = AccountStatement::new(&account(), &ledger(), 1_500, 3_500).unwrap();
+
+    let csv = statement.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("row_type,date,reason"));
+    assert_eq!(lines[1], "Opening,1500,,0,100,,,,");
+    assert_eq!(
+        lines[2],
+        "Operation,2000,Deposit,50,150,\"card, visa\",ref-2000,process-2000,op-2000"
+    );
+    assert_eq!(
+        lines[3],
+        "Operation,3000,Withdrawal,-20,130,\"wire \"\"A\"\"\",ref-3000,process-3000,op-3000"
+    );
+    assert_eq!(lines[4], "Closing,3500,,0,130,,,,");
+
+    let json: serde_json::Value = serde_json::from_str(&statement.to_json()).unwrap();
+    assert_eq!(json["account_id"], "account");
+    assert_eq!(json["opening_balance"], 100.0);
+    assert_eq!(json["closing_balance"], 130.0);
+    assert_eq!(json["operations"].as_array().unwrap().len(), 2);
+    assert_eq!(json["operations"][1]["comment"], "wire \"A\"");
+}
+
+#[test]
+fn csv_export_does_not_start_cells_with_a_formula() {
+    let mut ledger = ledger();
+    let mut snapshot = account();
+    snapshot.balance = 140.0;
+    snapshot.last_update_process_id = "@process".to_string();
+
+    ledger.append(
+        &snapshot,
+        &AccountManagerUpdateAccountBalanceGrpcRequest {
+            comment: "=HYPERLINK(\"http://x\",\"a\")".to_string(),
+            reference_transaction_id: Some("+ref".to_string()),
+            ..update_balance_request(10.0, UpdateBalanceReason::Deposit, "@process")
+        },
+        "-op",
+    );
+
+    let statement = AccountStatement::new(&account(), &ledger, 0, u64::MAX).unwrap();
+    let csv = statement.to_csv();
+    let line = csv
+        .lines()
+        .find(|x| x.contains("HYPERLINK"))
+        .unwrap()
+        .to_string();
+
+    assert!(line.ends_with(",\"'=HYPERLINK(\"\"http://x\"\",\"\"a\"\")\",'+ref,'@process,'-op"));
+    // Numbers keep their sign.
+    assert!(csv.contains(",-20,"));
+}
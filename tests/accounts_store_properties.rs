@@ -0,0 +1,403 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use accounts_manager::accounts_manager::{
+    AccountManagerUpdateAccountBalanceGrpcRequest, SearchAccounts, TradingDisabledReason,
+    UpdateBalanceReason,
+};
+use accounts_manager::{Account, AccountsStore, BalanceRules, OperationError};
+use common::{update_balance_request, AccountBuilder};
+use proptest::prelude::*;
+
+const TRADERS: usize = 4;
+const ACCOUNTS: usize = 8;
+const GROUPS: [&str; 3] = ["standard", "vip", "pro"];
+const REASONS: [TradingDisabledReason; 3] = [
+    TradingDisabledReason::Compliance,
+    TradingDisabledReason::RiskMarginCall,
+    TradingDisabledReason::Trader,
+];
+
+#[derive(Debug, Clone)]
+enum Operation {
+    Add {
+        trader: usize,
+        group: usize,
+        balance: i32,
+    },
+    UpdateBalance {
+        trader: usize,
+        account: usize,
+        delta: i32,
+        allow_negative_balance: bool,
+    },
+    UpdateTradingDisabled {
+        trader: usize,
+        account: usize,
+        trading_disabled: bool,
+        reason: usize,
+    },
+    UpdateTradingGroup {
+        trader: usize,
+        account: usize,
+        group: usize,
+    },
+}
+
+/// What the store is expected to hold, kept as plainly as possible.
+#[derive(Debug, Clone)]
+struct ModelAccount {
+    id: String,
+    trader_id: String,
+    initial_balance: f64,
+    applied_deltas: f64,
+    balance: f64,
+    trading_group: String,
+    disabled_reasons: BTreeSet<usize>,
+    /// Whether a balance update of the account was allowed to go negative.
+    allow_negative_balance: bool,
+}
+
+fn trader_id(trader: usize) -> String {
+    format!("trader-{}", trader)
+}
+
+fn account_id(account: usize) -> String {
+    format!("account-{}", account)
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+    // The last trader never gets an account, so it always hits TraderNotFound.
+    prop_oneof![
+        (0..TRADERS - 1, 0..GROUPS.len(), 0..1_000i32).prop_map(|(trader, group, balance)| {
+            Operation::Add {
+                trader,
+                group,
+                balance,
+            }
+        }),
+        (0..TRADERS, 0..ACCOUNTS, -1_000..1_000i32, any::<bool>()).prop_map(
+            |(trader, account, delta, allow_negative_balance)| Operation::UpdateBalance {
+                trader,
+                account,
+                delta,
+                allow_negative_balance,
+            }
+        ),
+        (0..TRADERS, 0..ACCOUNTS, any::<bool>(), 0..REASONS.len()).prop_map(
+            |(trader, account, trading_disabled, reason)| Operation::UpdateTradingDisabled {
+                trader,
+                account,
+                trading_disabled,
+                reason,
+            }
+        ),
+        (0..TRADERS, 0..ACCOUNTS, 0..GROUPS.len()).prop_map(|(trader, account, group)| {
+            Operation::UpdateTradingGroup {
+                trader,
+                account,
+                group,
+            }
+        }),
+    ]
+}
+
+fn new_account(id: &str, trader_id: &str, trading_group: &str, balance: f64) -> Account {
+    AccountBuilder::new(id)
+        .trader_id(trader_id)
+        .trading_group(trading_group)
+        .balance(balance)
+        .build()
+}
+
+/// Trading result, so the whole balance of the cash-only accounts is checked.
+fn balance_request(
+    trader_id: &str,
+    account_id: &str,
+    delta: f64,
+    allow_negative_balance: bool,
+) -> AccountManagerUpdateAccountBalanceGrpcRequest {
+    AccountManagerUpdateAccountBalanceGrpcRequest {
+        trader_id: trader_id.to_string(),
+        account_id: account_id.to_string(),
+        allow_negative_balance,
+        ..update_balance_request(delta, UpdateBalanceReason::TradingResult, "balance")
+    }
+}
+
+/// Error the store must return for an account that is not in the model.
+fn expected_lookup_error(model: &[ModelAccount], trader: usize) -> OperationError {
+    match model.iter().any(|x| x.trader_id == trader_id(trader)) {
+        true => OperationError::AccountNofFound,
+        false => OperationError::TraderNotFound,
+    }
+}
+
+fn find(model: &mut [ModelAccount], trader: usize, account: usize) -> Option<&mut ModelAccount> {
+    model
+        .iter_mut()
+        .find(|x| x.trader_id == trader_id(trader) && x.id == account_id(account))
+}
+
+fn assert_error<T: std::fmt::Debug>(
+    result: Result<T, OperationError>,
+    expected: OperationError,
+) -> Result<(), TestCaseError> {
+    match result {
+        Err(error) => {
+            prop_assert_eq!(format!("{:?}", error), format!("{:?}", expected));
+        }
+        Ok(value) => {
+            prop_assert!(false, "expected {:?}, got {:?}", expected, value);
+        }
+    }
+    Ok(())
+}
+
+fn apply(
+    store: &mut AccountsStore,
+    model: &mut Vec<ModelAccount>,
+    operation: Operation,
+) -> Result<(), TestCaseError> {
+    match operation {
+        Operation::Add {
+            trader,
+            group,
+            balance,
+        } => {
+            if model.len() == ACCOUNTS {
+                return Ok(());
+            }
+
+            let id = account_id(model.len());
+            let balance = balance as f64;
+            store.add_account(new_account(&id, &trader_id(trader), GROUPS[group], balance));
+            model.push(ModelAccount {
+                id,
+                trader_id: trader_id(trader),
+                initial_balance: balance,
+                applied_deltas: 0.0,
+                balance,
+                trading_group: GROUPS[group].to_string(),
+                disabled_reasons: BTreeSet::new(),
+                allow_negative_balance: false,
+            });
+        }
+        Operation::UpdateBalance {
+            trader,
+            account,
+            delta,
+            allow_negative_balance,
+        } => {
+            let delta = delta as f64;
+            let lookup_error = expected_lookup_error(model, trader);
+            let result = store
+                .update_balance_by_reason(
+                    &balance_request(
+                        &trader_id(trader),
+                        &account_id(account),
+                        delta,
+                        allow_negative_balance,
+                    ),
+                    &BalanceRules::default(),
+                )
+                .map(|x| x.balance);
+
+            match find(model, trader, account) {
+                None => assert_error(result, lookup_error)?,
+                Some(expected) => {
+                    if !allow_negative_balance && expected.balance + delta < 0.0 {
+                        assert_error(result, OperationError::NotEnoughBalance)?;
+                    } else {
+                        expected.balance += delta;
+                        expected.applied_deltas += delta;
+                        expected.allow_negative_balance |= allow_negative_balance;
+                        prop_assert_eq!(result.ok(), Some(expected.balance));
+                    }
+                }
+            }
+        }
+        Operation::UpdateTradingDisabled {
+            trader,
+            account,
+            trading_disabled,
+            reason,
+        } => {
+            let lookup_error = expected_lookup_error(model, trader);
+            let result = store.update_trading_disabled(
+                &trader_id(trader),
+                &account_id(account),
+                trading_disabled,
+                REASONS[reason],
+                None,
+                "disabled",
+            );
+
+            match find(model, trader, account) {
+                None => assert_error(result, lookup_error)?,
+                Some(expected) => {
+                    match trading_disabled {
+                        true => expected.disabled_reasons.insert(reason),
+                        false => expected.disabled_reasons.remove(&reason),
+                    };
+                    let change = result.unwrap();
+                    prop_assert_eq!(change.before.balance, change.after.balance);
+                    prop_assert_eq!(
+                        change.after.trading_disabled,
+                        !expected.disabled_reasons.is_empty()
+                    );
+                }
+            }
+        }
+        Operation::UpdateTradingGroup {
+            trader,
+            account,
+            group,
+        } => {
+            let lookup_error = expected_lookup_error(model, trader);
+            let result = store.update_trading_group(
+                &trader_id(trader),
+                &account_id(account),
+                GROUPS[group],
+                "group",
+            );
+
+            match find(model, trader, account) {
+                None => assert_error(result, lookup_error)?,
+                Some(expected) => {
+                    let change = result.unwrap();
+                    prop_assert_eq!(&change.before.trading_group, &expected.trading_group);
+                    expected.trading_group = GROUPS[group].to_string();
+                    prop_assert_eq!(&change.after.trading_group, &expected.trading_group);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn sorted_ids(accounts: Option<Vec<&Account>>) -> Vec<String> {
+    let mut result: Vec<String> = accounts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|x| x.id.clone())
+        .collect();
+    result.sort();
+    result
+}
+
+fn model_ids<'s>(model: impl Iterator<Item = &'s ModelAccount>) -> Vec<String> {
+    let mut result: Vec<String> = model.map(|x| x.id.clone()).collect();
+    result.sort();
+    result
+}
+
+fn assert_matches_model(
+    store: &AccountsStore,
+    model: &[ModelAccount],
+) -> Result<(), TestCaseError> {
+    for expected in model {
+        let account = store.get_account(&expected.trader_id, &expected.id);
+        prop_assert!(account.is_some());
+        let account = account.unwrap();
+
+        prop_assert_eq!(
+            account.balance,
+            expected.initial_balance + expected.applied_deltas
+        );
+        prop_assert_eq!(&account.trading_group, &expected.trading_group);
+        prop_assert_eq!(
+            account.trading_disabled,
+            !expected.disabled_reasons.is_empty()
+        );
+        prop_assert_eq!(
+            store.get_trader_id_by_account_id(&expected.id),
+            Some(expected.trader_id.clone())
+        );
+
+        if !expected.allow_negative_balance {
+            prop_assert!(account.balance >= 0.0);
+        }
+    }
+
+    for trader in 0..TRADERS {
+        let trader_id = trader_id(trader);
+        prop_assert_eq!(
+            sorted_ids(store.get_accounts(&trader_id)),
+            model_ids(model.iter().filter(|x| x.trader_id == trader_id))
+        );
+    }
+
+    for group in GROUPS {
+        prop_assert_eq!(
+            sorted_ids(store.get_accounts_by_trading_group(group)),
+            model_ids(model.iter().filter(|x| x.trading_group == group))
+        );
+
+        let search = SearchAccounts {
+            trading_group: Some(group.to_string()),
+            disabled: Some(true),
+            ..Default::default()
+        };
+        prop_assert_eq!(
+            sorted_ids(store.search(&search)),
+            model_ids(
+                model
+                    .iter()
+                    .filter(|x| x.trading_group == group && !x.disabled_reasons.is_empty())
+            )
+        );
+    }
+
+    prop_assert_eq!(
+        sorted_ids(store.search(&SearchAccounts::default())),
+        model_ids(model.iter())
+    );
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn store_agrees_with_model(operations in prop::collection::vec(operation(), 1..64)) {
+        let mut store = AccountsStore::new(vec![]);
+        let mut model = vec![];
+
+        for operation in operations {
+            apply(&mut store, &mut model, operation)?;
+            assert_matches_model(&store, &model)?;
+        }
+    }
+
+    #[test]
+    fn balance_never_goes_negative_without_permission(
+        initial_balance in 0..1_000i32,
+        deltas in prop::collection::vec(-1_000..1_000i32, 1..64),
+    ) {
+        let mut store = AccountsStore::new(vec![new_account(
+            "account",
+            "trader",
+            "standard",
+            initial_balance as f64,
+        )]);
+        let mut applied = 0.0;
+
+        for delta in deltas {
+            if store
+                .update_balance_by_reason(
+                    &balance_request("trader", "account", delta as f64, false),
+                    &BalanceRules::default(),
+                )
+                .is_ok()
+            {
+                applied += delta as f64;
+            }
+
+            let balance = store.get_account("trader", "account").unwrap().balance;
+            prop_assert!(balance >= 0.0);
+            prop_assert_eq!(balance, initial_balance as f64 + applied);
+        }
+    }
+}
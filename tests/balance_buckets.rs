@@ -0,0 +1,121 @@
+mod common;
+
+use accounts_manager::accounts_manager::UpdateBalanceReason;
+use accounts_manager::{Account, BalanceBucket, BalanceBuckets, BalanceRules, OperationError};
+use common::AccountBuilder;
+
+fn account(cash: f64, bonus: f64, credit: f64) -> Account {
+    AccountBuilder::new("account")
+        .buckets(BalanceBuckets {
+            cash,
+            bonus,
+            credit,
+        })
+        .build()
+}
+
+#[test]
+fn deltas_are_routed_by_reason() {
+    let rules = BalanceRules::default();
+    let account = account(100.0, 0.0, 0.0);
+
+    for (reason, bucket) in [
+        (UpdateBalanceReason::Deposit, BalanceBucket::Cash),
+        (UpdateBalanceReason::TradingResult, BalanceBucket::Cash),
+        (UpdateBalanceReason::Bonus, BalanceBucket::Bonus),
+        (UpdateBalanceReason::Voucher, BalanceBucket::Bonus),
+        (UpdateBalanceReason::Credit, BalanceBucket::Credit),
+    ] {
+        let delta = rules.split_delta(&account, 10.0, reason, false).unwrap();
+        assert_eq!(delta.get(bucket), 10.0, "{:?}", reason);
+        assert_eq!(delta.total(), 10.0, "{:?}", reason);
+    }
+}
+
+#[test]
+fn withdrawals_are_checked_against_cash_only() {
+    let rules = BalanceRules::default();
+    let account = account(100.0, 50.0, 20.0);
+
+    assert!(matches!(
+        rules.split_delta(&account, -120.0, UpdateBalanceReason::Withdrawal, false),
+        Err(OperationError::NotEnoughBalance)
+    ));
+    assert!(matches!(
+        rules.split_delta(&account, -60.0, UpdateBalanceReason::Bonus, false),
+        Err(OperationError::NotEnoughBalance)
+    ));
+
+    let delta = rules
+        .split_delta(&account, -100.0, UpdateBalanceReason::Withdrawal, false)
+        .unwrap();
+    assert_eq!(delta, BalanceBuckets::cash(-100.0));
+}
+
+#[test]
+fn trading_losses_consume_buckets_in_the_configured_order() {
+    let account = account(100.0, 50.0, 20.0);
+
+    let delta = BalanceRules::default()
+        .split_delta(&account, -130.0, UpdateBalanceReason::TradingResult, false)
+        .unwrap();
+    assert_eq!(
+        delta,
+        BalanceBuckets {
+            cash: -100.0,
+            bonus: -30.0,
+            credit: 0.0,
+        }
+    );
+
+    let bonus_first = BalanceRules {
+        trading_loss_order: vec![BalanceBucket::Bonus, BalanceBucket::Credit],
+    };
+    let delta = bonus_first
+        .split_delta(&account, -80.0, UpdateBalanceReason::TradingResult, false)
+        .unwrap();
+    assert_eq!(
+        delta,
+        BalanceBuckets {
+            cash: -10.0,
+            bonus: -50.0,
+            credit: -20.0,
+        }
+    );
+
+    assert!(matches!(
+        bonus_first.split_delta(&account, -200.0, UpdateBalanceReason::TradingResult, false),
+        Err(OperationError::NotEnoughBalance)
+    ));
+
+    // What no bucket covers ends up as negative cash.
+    let delta = bonus_first
+        .split_delta(&account, -200.0, UpdateBalanceReason::TradingResult, true)
+        .unwrap();
+    assert_eq!(delta.cash, -130.0);
+    assert_eq!(delta.total(), -200.0);
+}
+
+#[test]
+fn bonus_conversion_never_exceeds_the_bonus_balance() {
+    let rules = BalanceRules::default();
+    let account = account(100.0, 50.0, 20.0);
+
+    for allow_negative_balance in [false, true] {
+        assert!(matches!(
+            rules.split_delta(
+                &account,
+                60.0,
+                UpdateBalanceReason::BonusConversion,
+                allow_negative_balance
+            ),
+            Err(OperationError::NotEnoughBalance)
+        ));
+    }
+
+    let delta = rules
+        .split_delta(&account, 50.0, UpdateBalanceReason::BonusConversion, true)
+        .unwrap();
+    assert_eq!(delta.cash, 50.0);
+    assert_eq!(delta.bonus, -50.0);
+}
@@ -0,0 +1,382 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use accounts_manager::accounts_manager::UpdateBalanceReason;
+use accounts_manager::{
+    Account, AccountsCache, AccountsStore, BalanceLedger, BalanceRules, InMemoryEventsPublisher,
+    LedgerCheckpoint, LedgerMismatch, OperationError, OutboxEvent, SbEventsOutbox,
+    MAX_LEDGER_ENTRIES_PER_ACCOUNT,
+};
+use cfd_engine_sb_contracts::AccountPersistEvent;
+use common::{accounts_cache, update_balance_request, AccountBuilder};
+
+fn account(balance: f64) -> Account {
+    AccountBuilder::new("account").balance(balance).build()
+}
+
+#[tokio::test]
+async fn balance_updates_are_chained_in_the_ledger() {
+    let cache = accounts_cache(vec![account(100.0)]);
+
+    cache
+        .update_balance(
+            &update_balance_request(50.0, UpdateBalanceReason::Deposit, "process"),
+            &BalanceRules::default(),
+            Duration::from_secs(60),
+            "op-1",
+            |_, _, _| vec![],
+        )
+        .await
+        .unwrap();
+    cache
+        .update_balance(
+            &update_balance_request(-30.0, UpdateBalanceReason::Withdrawal, "process"),
+            &BalanceRules::default(),
+            Duration::from_secs(60),
+            "op-2",
+            |_, _, _| vec![],
+        )
+        .await
+        .unwrap();
+    assert!(cache
+        .update_balance(
+            &update_balance_request(-1000.0, UpdateBalanceReason::Withdrawal, "process"),
+            &BalanceRules::default(),
+            Duration::from_secs(60),
+            "op-3",
+            |_, _, _| vec![],
+        )
+        .await
+        .is_err());
+
+    assert_eq!(cache.verify_ledger().await, (1, vec![]));
+
+    let store = cache.accounts_store.read().await;
+    let entries = store.ledger.get_entries("account");
+
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].reason, None);
+    assert_eq!(entries[0].new_balance, 100.0);
+    assert_eq!(entries[1].sequence, 1);
+    assert_eq!(entries[1].previous_balance, 100.0);
+    assert_eq!(entries[1].reason, Some(UpdateBalanceReason::Deposit));
+    assert_eq!(entries[2].operation_id, "op-2");
+    assert_eq!(entries[2].new_balance, 120.0);
+}
+
+#[test]
+fn store_is_rebuilt_from_the_ledger() {
+    let mut ledger = BalanceLedger::new();
+    let mut snapshot = account(100.0);
+    ledger.open(&snapshot);
+
+    snapshot.balance = 175.0;
+    snapshot.buckets.cash = 175.0;
+    ledger.append(
+        &snapshot,
+        &update_balance_request(75.0, UpdateBalanceReason::Deposit, "process"),
+        "op-1",
+    );
+
+    // The snapshot is stale; the ledger wins, buckets included.
+    let (store, mismatches) = AccountsStore::rebuild(vec![account(100.0)], ledger, 5_000);
+    let restored = store.get_account("trader", "account").unwrap();
+    assert_eq!(restored.balance, 175.0);
+    assert_eq!(restored.buckets.cash, 175.0);
+    assert_eq!(
+        mismatches,
+        vec![LedgerMismatch::BalanceMismatch {
+            account_id: "account".to_string(),
+            cached: 100.0,
+            replayed: 175.0,
+        }]
+    );
+    assert!(store.verify_ledger().is_empty());
+
+    // Without persisted history the snapshot starts a new one.
+    let (store, mismatches) =
+        AccountsStore::rebuild(vec![account(100.0)], BalanceLedger::new(), 5_000);
+    assert!(mismatches.is_empty());
+    let entries = store.ledger.get_entries("account");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].date, 5_000);
+    assert_eq!(entries[0].new_balance, 100.0);
+}
+
+#[test]
+fn broken_ledger_is_reported_on_rebuild() {
+    let mut ledger = BalanceLedger::new();
+    let mut snapshot = account(100.0);
+    ledger.open(&snapshot);
+    snapshot.balance = 150.0;
+    ledger.append(
+        &snapshot,
+        &update_balance_request(10.0, UpdateBalanceReason::Deposit, "process"),
+        "op-1",
+    );
+
+    let (store, mismatches) = AccountsStore::rebuild(vec![account(100.0)], ledger, 5_000);
+    assert_eq!(
+        mismatches,
+        vec![LedgerMismatch::BrokenChain {
+            account_id: "account".to_string(),
+            sequence: 1,
+        }]
+    );
+    assert_eq!(
+        store.get_account("trader", "account").unwrap().balance,
+        100.0
+    );
+    assert!(store.verify_ledger().is_empty());
+}
+
+#[tokio::test]
+async fn ledger_is_checked_from_the_last_checkpoint() {
+    let cache = accounts_cache(vec![account(100.0)]);
+    let mut checkpoints = HashMap::new();
+
+    assert!(cache.verify_ledger_since(&mut checkpoints).await.is_empty());
+    assert_eq!(
+        checkpoints["account"],
+        LedgerCheckpoint {
+            sequence: 0,
+            balance: 100.0
+        }
+    );
+
+    cache
+        .update_balance(
+            &update_balance_request(50.0, UpdateBalanceReason::Deposit, "process"),
+            &BalanceRules::default(),
+            Duration::from_secs(60),
+            "op-1",
+            |_, _, _| vec![],
+        )
+        .await
+        .unwrap();
+
+    assert!(cache.verify_ledger_since(&mut checkpoints).await.is_empty());
+    assert_eq!(
+        checkpoints["account"],
+        LedgerCheckpoint {
+            sequence: 1,
+            balance: 150.0
+        }
+    );
+
+    cache
+        .accounts_store
+        .write()
+        .await
+        .accounts
+        .get_mut("trader")
+        .unwrap()
+        .get_mut("account")
+        .unwrap()
+        .balance += 10.0;
+
+    // The checkpoint stays put until the account checks out again.
+    for _ in 0..2 {
+        assert_eq!(
+            cache.verify_ledger_since(&mut checkpoints).await,
+            vec![LedgerMismatch::BalanceMismatch {
+                account_id: "account".to_string(),
+                cached: 160.0,
+                replayed: 150.0,
+            }]
+        );
+        assert_eq!(checkpoints["account"].sequence, 1);
+    }
+}
+
+#[test]
+fn out_of_band_changes_are_detected() {
+    let mut store = AccountsStore::new(vec![account(100.0)]);
+
+    // Bypasses every flow, so the ledger never hears about it.
+    let cached = store
+        .accounts
+        .get_mut("trader")
+        .unwrap()
+        .get_mut("account")
+        .unwrap();
+    cached.balance += 10.0;
+    cached.buckets.cash += 10.0;
+
+    assert_eq!(
+        store.verify_ledger(),
+        vec![LedgerMismatch::BalanceMismatch {
+            account_id: "account".to_string(),
+            cached: 110.0,
+            replayed: 100.0,
+        }]
+    );
+
+    let mut ledger = BalanceLedger::new();
+    let mut snapshot = account(100.0);
+    ledger.open(&snapshot);
+    snapshot.balance = 150.0;
+    ledger.append(
+        &snapshot,
+        &update_balance_request(10.0, UpdateBalanceReason::Deposit, "process"),
+        "op-1",
+    );
+
+    assert_eq!(
+        ledger.replay().unwrap_err(),
+        vec![LedgerMismatch::BrokenChain {
+            account_id: "account".to_string(),
+            sequence: 1,
+        }]
+    );
+}
+
+#[test]
+fn balance_is_answered_at_a_point_in_time() {
+    let mut ledger = BalanceLedger::new();
+    let mut snapshot = account(100.0);
+    snapshot.create_date = 1_000;
+    ledger.open(&snapshot);
+
+    for (date, delta) in [(2_000, 50.0), (3_000, -20.0), (4_000, 10.0)] {
+        snapshot.balance += delta;
+        snapshot.last_update_date = date;
+        ledger.append(
+            &snapshot,
+            &update_balance_request(delta, UpdateBalanceReason::Deposit, "process"),
+            "op",
+        );
+    }
+
+    assert_eq!(
+        ledger.get_entry_at(&snapshot, 2_500).unwrap().new_balance,
+        150.0
+    );
+    assert_eq!(
+        ledger.get_entry_at(&snapshot, 3_000).unwrap().new_balance,
+        130.0
+    );
+    assert!(matches!(
+        ledger.get_entry_at(&snapshot, 500),
+        Err(OperationError::AccountNofFound)
+    ));
+
+    // The last entry before the cutoff stays as the starting point.
+    assert_eq!(ledger.prune(3_500), 2);
+    assert_eq!(
+        ledger.get_entry_at(&snapshot, 3_200).unwrap().new_balance,
+        130.0
+    );
+    assert!(matches!(
+        ledger.get_entry_at(&snapshot, 2_500),
+        Err(OperationError::InvalidRequest(_))
+    ));
+    assert_eq!(ledger.replay().unwrap()["account"], 140.0);
+}
+
+#[test]
+fn balance_before_the_loaded_history_is_not_answered() {
+    let mut snapshot = account(100.0);
+    snapshot.create_date = 1_000;
+
+    // Nothing was persisted, so the history starts when the store is loaded.
+    let (store, _) = AccountsStore::rebuild(vec![snapshot.clone()], BalanceLedger::new(), 5_000);
+
+    assert_eq!(
+        store
+            .ledger
+            .get_entry_at(&snapshot, 6_000)
+            .unwrap()
+            .new_balance,
+        100.0
+    );
+    assert!(matches!(
+        store.ledger.get_entry_at(&snapshot, 2_000),
+        Err(OperationError::InvalidRequest(_))
+    ));
+    assert!(matches!(
+        store.ledger.get_entry_at(&snapshot, 500),
+        Err(OperationError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn ledger_keeps_a_bounded_number_of_entries_per_account() {
+    let mut ledger = BalanceLedger::new();
+    let mut snapshot = account(0.0);
+    ledger.open(&snapshot);
+
+    for _ in 0..MAX_LEDGER_ENTRIES_PER_ACCOUNT * 2 {
+        snapshot.balance += 1.0;
+        snapshot.last_update_date += 1;
+        ledger.append(
+            &snapshot,
+            &update_balance_request(1.0, UpdateBalanceReason::Deposit, "process"),
+            "op",
+        );
+    }
+
+    let entries = ledger.get_entries("account");
+    assert!(entries.len() <= MAX_LEDGER_ENTRIES_PER_ACCOUNT + MAX_LEDGER_ENTRIES_PER_ACCOUNT / 10);
+    assert_eq!(
+        entries.last().unwrap().sequence,
+        MAX_LEDGER_ENTRIES_PER_ACCOUNT as u64 * 2
+    );
+    assert_eq!(
+        ledger.replay().unwrap()["account"],
+        MAX_LEDGER_ENTRIES_PER_ACCOUNT as f64 * 2.0
+    );
+    assert!(matches!(
+        ledger.get_entry_at(&snapshot, 0),
+        Err(OperationError::InvalidRequest(_))
+    ));
+}
+
+#[tokio::test]
+async fn changes_are_refused_while_the_outbox_is_full() {
+    let sb_events_outbox = Arc::new(SbEventsOutbox::new(
+        Arc::new(InMemoryEventsPublisher::new()),
+        1,
+    ));
+    let cache = AccountsCache::new("live", vec![account(100.0)], sb_events_outbox.clone());
+    let deposit =
+        |process_id| update_balance_request(50.0, UpdateBalanceReason::Deposit, process_id);
+
+    cache
+        .update_balance(
+            &deposit("deposit-1"),
+            &BalanceRules::default(),
+            Duration::from_secs(60),
+            "op-1",
+            |_, _, _| {
+                vec![OutboxEvent::Persist(Box::new(
+                    AccountPersistEvent::default(),
+                ))]
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(sb_events_outbox.get_queued_count(), 1);
+
+    let result = cache
+        .update_balance(
+            &deposit("deposit-2"),
+            &BalanceRules::default(),
+            Duration::from_secs(60),
+            "op-2",
+            |_, _, _| vec![],
+        )
+        .await;
+    assert!(matches!(result, Err(OperationError::Unavailable(_))));
+    assert_eq!(
+        cache
+            .get_account("trader", "account")
+            .await
+            .unwrap()
+            .balance,
+        150.0
+    );
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use accounts_manager::accounts_manager::accounts_manager_grpc_service_server::AccountsManagerGrpcService;
+use accounts_manager::accounts_manager::*;
+use accounts_manager::{convert_earned_bonus_grants, expire_bonus_grants, GrpcService};
+use common::{app, service_with, settings, update_balance_request, AccountBuilder};
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio_stream::StreamExt;
+use tonic::Request;
+
+async fn update_balance(
+    service: &GrpcService,
+    request: AccountManagerUpdateAccountBalanceGrpcRequest,
+) -> AccountManagerUpdateAccountBalanceGrpcResponse {
+    service
+        .update_client_account_balance(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner()
+}
+
+async fn active_bonuses(service: &GrpcService) -> Vec<BonusGrantGrpcModel> {
+    service
+        .get_active_bonuses(Request::new(AccountManagerGetActiveBonusesGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: "account".to_string(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .map(|x| x.unwrap())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn unearned_bonus_is_taken_back_and_earned_one_converted() {
+    let (app, _) = app(settings(), vec![AccountBuilder::new("account").build()]).await;
+    let service = GrpcService::new(app.clone());
+    let now = chrono::offset::Utc::now().timestamp_millis() as u64;
+
+    let mut request = update_balance_request(50.0, UpdateBalanceReason::Bonus, "bonus-1");
+    request.bonus_expires_at = Some(now - 1_000);
+    update_balance(&service, request).await;
+
+    let mut request = update_balance_request(30.0, UpdateBalanceReason::Bonus, "bonus-2");
+    request.bonus_turnover_required = Some(100.0);
+    update_balance(&service, request).await;
+
+    let bonuses = active_bonuses(&service).await;
+    assert_eq!(bonuses.len(), 2);
+    assert!(bonuses
+        .iter()
+        .all(|x| x.status == BonusGrantStatus::Active as i32));
+    assert!(bonuses[1].expires_at > now);
+
+    let mut request = update_balance_request(5.0, UpdateBalanceReason::TradingResult, "trade");
+    request.trading_volume = Some(100.0);
+    update_balance(&service, request).await;
+
+    let bonuses = active_bonuses(&service).await;
+    assert_eq!(bonuses[0].turnover, 100.0);
+    assert_eq!(bonuses[0].status, BonusGrantStatus::Active as i32);
+    assert_eq!(bonuses[1].status, BonusGrantStatus::Earned as i32);
+
+    let telemetry = MyTelemetryContext::new();
+    expire_bonus_grants(&app, now, &telemetry).await.unwrap();
+    expire_bonus_grants(&app, now, &telemetry).await.unwrap();
+
+    let bonuses = active_bonuses(&service).await;
+    assert_eq!(bonuses.len(), 1);
+    assert_eq!(bonuses[0].amount, 30.0);
+
+    let account = app
+        .accounts_caches
+        .get("")
+        .unwrap()
+        .get_account("trader", "account")
+        .await
+        .unwrap();
+    assert_eq!(account.balance, 135.0);
+    assert_eq!(account.buckets.bonus, 30.0);
+
+    convert_earned_bonus_grants(&app, &telemetry).await.unwrap();
+    convert_earned_bonus_grants(&app, &telemetry).await.unwrap();
+
+    assert!(active_bonuses(&service).await.is_empty());
+
+    let account = app
+        .accounts_caches
+        .get("")
+        .unwrap()
+        .get_account("trader", "account")
+        .await
+        .unwrap();
+    assert_eq!(account.balance, 135.0);
+    assert_eq!(account.buckets.bonus, 0.0);
+    assert_eq!(account.buckets.cash, 135.0);
+}
+
+#[tokio::test]
+async fn bonus_debit_reduces_the_oldest_grants() {
+    let (app, publisher) = app(settings(), vec![AccountBuilder::new("account").build()]).await;
+    let service = GrpcService::new(app.clone());
+
+    update_balance(
+        &service,
+        update_balance_request(50.0, UpdateBalanceReason::Bonus, "bonus-1"),
+    )
+    .await;
+    update_balance(
+        &service,
+        update_balance_request(30.0, UpdateBalanceReason::Bonus, "bonus-2"),
+    )
+    .await;
+    update_balance(
+        &service,
+        update_balance_request(-60.0, UpdateBalanceReason::Bonus, "debit"),
+    )
+    .await;
+
+    let bonuses = active_bonuses(&service).await;
+    assert_eq!(bonuses.len(), 1);
+    assert_eq!(bonuses[0].amount, 20.0);
+
+    let events = publisher.persist_events.lock().await;
+    let event = &events.last().unwrap().1;
+    // The shared contract carries cash as the balance and the rest beside it.
+    let account = event
+        .update_account_event
+        .as_ref()
+        .unwrap()
+        .account_after_update
+        .as_ref()
+        .unwrap();
+    assert_eq!(account.balance, 100.0);
+    assert_eq!(account.bonus_balance, 20.0);
+    let grants = &event.bonus_grants;
+    assert_eq!(grants.len(), 2);
+    assert_eq!(grants[0].status, BonusGrantStatus::Expired as i32);
+    assert_eq!(grants[0].amount, 0.0);
+    assert_eq!(grants[1].status, BonusGrantStatus::Active as i32);
+    assert_eq!(grants[1].amount, 20.0);
+}
+
+#[tokio::test]
+async fn bonus_conversion_is_not_accepted_from_callers() {
+    let (service, _) = service_with(settings(), vec![AccountBuilder::new("account").build()]).await;
+
+    update_balance(
+        &service,
+        update_balance_request(10.0, UpdateBalanceReason::Bonus, "bonus"),
+    )
+    .await;
+
+    for (delta, allow_negative_balance) in [(5.0, false), (20.0, true)] {
+        let mut request =
+            update_balance_request(delta, UpdateBalanceReason::BonusConversion, "conversion");
+        request.allow_negative_balance = allow_negative_balance;
+
+        let response = update_balance(&service, request).await;
+        assert_eq!(
+            response.result,
+            AccountsManagerOperationResult::InvalidRequest as i32
+        );
+    }
+}
+
+#[tokio::test]
+async fn bonus_fields_are_rejected_outside_of_a_grant() {
+    let (service, _) = service_with(settings(), vec![AccountBuilder::new("account").build()]).await;
+
+    let mut request = update_balance_request(10.0, UpdateBalanceReason::Deposit, "deposit");
+    request.bonus_turnover_required = Some(100.0);
+    let response = update_balance(&service, request).await;
+    assert_eq!(
+        response.result,
+        AccountsManagerOperationResult::InvalidRequest as i32
+    );
+
+    let mut request = update_balance_request(10.0, UpdateBalanceReason::Deposit, "deposit");
+    request.trading_volume = Some(100.0);
+    let response = update_balance(&service, request).await;
+    assert_eq!(
+        response.result,
+        AccountsManagerOperationResult::InvalidRequest as i32
+    );
+}
+
+#[tokio::test]
+async fn voucher_opens_a_grant_that_expires() {
+    let (app, _) = app(settings(), vec![AccountBuilder::new("account").build()]).await;
+    let service = GrpcService::new(app.clone());
+    let now = chrono::offset::Utc::now().timestamp_millis() as u64;
+
+    let mut request = update_balance_request(25.0, UpdateBalanceReason::Voucher, "voucher");
+    request.bonus_expires_at = Some(now - 1_000);
+    let response = update_balance(&service, request).await;
+    assert_eq!(response.result, 0);
+
+    let bonuses = active_bonuses(&service).await;
+    assert_eq!(bonuses.len(), 1);
+    assert_eq!(bonuses[0].amount, 25.0);
+
+    expire_bonus_grants(&app, now, &MyTelemetryContext::new())
+        .await
+        .unwrap();
+
+    assert!(active_bonuses(&service).await.is_empty());
+
+    let account = app
+        .accounts_caches
+        .get("")
+        .unwrap()
+        .get_account("trader", "account")
+        .await
+        .unwrap();
+    assert_eq!(account.balance, 100.0);
+    assert_eq!(account.buckets.bonus, 0.0);
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use accounts_manager::accounts_manager::UpdateBalanceReason;
+use accounts_manager::{
+    authenticate, AuthSettingsModel, ServicePermissionsSettingsModel, API_KEY_HEADER,
+    CALLER_ID_HEADER,
+};
+
+fn auth_settings() -> AuthSettingsModel {
+    AuthSettingsModel {
+        api_keys: HashMap::from([
+            ("trading-engine".to_string(), "engine-key".to_string()),
+            ("payments".to_string(), "payments-key".to_string()),
+            ("reports".to_string(), "reports-key".to_string()),
+        ]),
+        permissions: HashMap::from([
+            (
+                "trading-engine".to_string(),
+                ServicePermissionsSettingsModel {
+                    methods: vec!["UpdateClientAccountBalance".to_string()],
+                    balance_reasons: Some(vec!["TradingResult".to_string()]),
+                },
+            ),
+            (
+                "payments".to_string(),
+                ServicePermissionsSettingsModel {
+                    methods: vec!["*".to_string()],
+                    balance_reasons: Some(vec!["Deposit".to_string(), "Withdrawal".to_string()]),
+                },
+            ),
+        ]),
+    }
+}
+
+fn request_with_header(name: &'static str, value: &str) -> tonic::Request<()> {
+    let mut request = tonic::Request::new(());
+    request.metadata_mut().insert(name, value.parse().unwrap());
+    request
+}
+
+#[test]
+fn without_auth_settings_everything_is_allowed() {
+    let request = request_with_header(CALLER_ID_HEADER, "backoffice");
+    let caller = authenticate(&request, None).unwrap();
+
+    assert_eq!(caller.name, "backoffice");
+    assert!(caller
+        .ensure_method_allowed("BulkUpdateTradingGroup")
+        .is_ok());
+    assert!(caller
+        .ensure_balance_reason_allowed(UpdateBalanceReason::Deposit)
+        .is_ok());
+}
+
+#[test]
+fn missing_or_unknown_api_key_is_rejected() {
+    let settings = auth_settings();
+
+    let error = authenticate(&tonic::Request::new(()), Some(&settings)).unwrap_err();
+    assert_eq!(error.code(), tonic::Code::Unauthenticated);
+
+    let request = request_with_header(API_KEY_HEADER, "wrong-key");
+    let error = authenticate(&request, Some(&settings)).unwrap_err();
+    assert_eq!(error.code(), tonic::Code::Unauthenticated);
+}
+
+#[test]
+fn permissions_limit_methods_and_balance_reasons() {
+    let settings = auth_settings();
+
+    let request = request_with_header(API_KEY_HEADER, "engine-key");
+    let engine = authenticate(&request, Some(&settings)).unwrap();
+    assert_eq!(engine.name, "trading-engine");
+    assert!(engine
+        .ensure_method_allowed("UpdateClientAccountBalance")
+        .is_ok());
+    assert_eq!(
+        engine.ensure_method_allowed("Search").unwrap_err().code(),
+        tonic::Code::PermissionDenied
+    );
+    assert!(engine
+        .ensure_balance_reason_allowed(UpdateBalanceReason::TradingResult)
+        .is_ok());
+    assert_eq!(
+        engine
+            .ensure_balance_reason_allowed(UpdateBalanceReason::Deposit)
+            .unwrap_err()
+            .code(),
+        tonic::Code::PermissionDenied
+    );
+
+    let request = request_with_header(API_KEY_HEADER, "payments-key");
+    let payments = authenticate(&request, Some(&settings)).unwrap();
+    assert!(payments.ensure_method_allowed("Search").is_ok());
+    assert!(payments
+        .ensure_balance_reason_allowed(UpdateBalanceReason::Withdrawal)
+        .is_ok());
+    assert!(payments
+        .ensure_balance_reason_allowed(UpdateBalanceReason::TradingResult)
+        .is_err());
+
+    let request = request_with_header(API_KEY_HEADER, "reports-key");
+    let reports = authenticate(&request, Some(&settings)).unwrap();
+    assert!(reports.ensure_method_allowed("GetClientAccount").is_err());
+}
@@ -0,0 +1,159 @@
+// Every test crate uses only part of the fixtures.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use accounts_manager::accounts_manager::{
+    AccountManagerUpdateAccountBalanceGrpcRequest, UpdateBalanceReason,
+};
+use accounts_manager::{
+    Account, AccountsCache, AppContext, BalanceBuckets, GrpcService, InMemoryAccountsLoader,
+    InMemoryEventsPublisher, InMemorySettingsSource, SbEventsOutbox, SettingsModel,
+    MAX_QUEUED_SB_EVENTS,
+};
+
+pub const ACCOUNT_TYPE: &str = "live";
+
+pub fn settings() -> SettingsModel {
+    SettingsModel {
+        my_sb_tcp_host_port: "sb:6421".to_string(),
+        default_account_balance: 100.0,
+        default_account_trading_group: "standard".to_string(),
+        accounts_manager_persistence_grpc_url: "http://persistence:8888".to_string(),
+        accounts_default_currency: Some("USD".to_string()),
+        default_accounts: None,
+        rate_limits: None,
+        auth: None,
+        account_types: None,
+        balance_history_retention_days: None,
+        eod_cutoff_utc: None,
+        trading_loss_buckets: None,
+        bonus_expiration_days: None,
+        persistence_loads: None,
+        my_telemetry: "".to_string(),
+        seq_conn_string: "".to_string(),
+        _type: ACCOUNT_TYPE.to_string(),
+    }
+}
+
+/// App on top of the in-memory doubles, with `accounts` loaded as the
+/// `ACCOUNT_TYPE` accounts.
+pub async fn app(
+    settings: SettingsModel,
+    accounts: Vec<Account>,
+) -> (Arc<AppContext>, Arc<InMemoryEventsPublisher>) {
+    let events_publisher = Arc::new(InMemoryEventsPublisher::new());
+    let app = AppContext::new(
+        Arc::new(InMemorySettingsSource::new(settings)),
+        &InMemoryAccountsLoader::new().with_accounts(ACCOUNT_TYPE, accounts),
+        events_publisher.clone(),
+    )
+    .await;
+
+    (Arc::new(app), events_publisher)
+}
+
+pub async fn service_with(
+    settings: SettingsModel,
+    accounts: Vec<Account>,
+) -> (GrpcService, Arc<InMemoryEventsPublisher>) {
+    let (app, events_publisher) = app(settings, accounts).await;
+    (GrpcService::new(app), events_publisher)
+}
+
+pub async fn service() -> (GrpcService, Arc<InMemoryEventsPublisher>) {
+    service_with(settings(), vec![]).await
+}
+
+/// Cache of `ACCOUNT_TYPE` accounts queueing its events to an in-memory bus.
+pub fn accounts_cache(accounts: Vec<Account>) -> AccountsCache {
+    let sb_events_outbox = SbEventsOutbox::new(
+        Arc::new(InMemoryEventsPublisher::new()),
+        MAX_QUEUED_SB_EVENTS,
+    );
+
+    AccountsCache::new(ACCOUNT_TYPE, accounts, Arc::new(sb_events_outbox))
+}
+
+/// Account of `trader` holding 100 USD in cash unless told otherwise.
+pub struct AccountBuilder {
+    account: Account,
+}
+
+impl AccountBuilder {
+    pub fn new(id: &str) -> Self {
+        Self {
+            account: Account {
+                id: id.to_string(),
+                currency: "USD".to_string(),
+                trader_id: "trader".to_string(),
+                create_date: 0,
+                last_update_date: 0,
+                last_update_process_id: "create".to_string(),
+                balance: 100.0,
+                buckets: BalanceBuckets::cash(100.0),
+                trading_disabled: false,
+                create_process_id: "create".to_string(),
+                trading_group: "standard".to_string(),
+                metadata: vec![],
+                trading_disabled_reasons: vec![],
+                version: 1,
+                account_type: ACCOUNT_TYPE.to_string(),
+            },
+        }
+    }
+
+    pub fn trader_id(mut self, trader_id: &str) -> Self {
+        self.account.trader_id = trader_id.to_string();
+        self
+    }
+
+    pub fn trading_group(mut self, trading_group: &str) -> Self {
+        self.account.trading_group = trading_group.to_string();
+        self
+    }
+
+    pub fn created_at(mut self, date: u64) -> Self {
+        self.account.create_date = date;
+        self.account.last_update_date = date;
+        self
+    }
+
+    pub fn balance(self, cash: f64) -> Self {
+        self.buckets(BalanceBuckets::cash(cash))
+    }
+
+    pub fn buckets(mut self, buckets: BalanceBuckets) -> Self {
+        self.account.balance = buckets.total();
+        self.account.buckets = buckets;
+        self
+    }
+
+    pub fn build(self) -> Account {
+        self.account
+    }
+}
+
+/// Balance update of the `trader` `account`.
+pub fn update_balance_request(
+    delta: f64,
+    reason: UpdateBalanceReason,
+    process_id: &str,
+) -> AccountManagerUpdateAccountBalanceGrpcRequest {
+    AccountManagerUpdateAccountBalanceGrpcRequest {
+        trader_id: "trader".to_string(),
+        account_id: "account".to_string(),
+        delta,
+        comment: "comment".to_string(),
+        process_id: process_id.to_string(),
+        allow_negative_balance: false,
+        reason: reason as i32,
+        reference_transaction_id: None,
+        same_response_process_id: false,
+        account_type: "".to_string(),
+        expected_version: None,
+        bonus_expires_at: None,
+        bonus_turnover_required: None,
+        trading_volume: None,
+    }
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use std::time::Duration;
+
+use accounts_manager::accounts_manager::accounts_manager_grpc_service_server::AccountsManagerGrpcService;
+use accounts_manager::accounts_manager::*;
+use accounts_manager::{
+    capture_daily_balances, get_last_eod_cutoff, Account, GrpcService, OperationError,
+    SettingsModel, DAILY_BALANCES_CHUNK_SIZE,
+};
+use common::{app, settings, AccountBuilder};
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio_stream::StreamExt;
+use tonic::Request;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+fn account(id: &str, create_date: u64) -> Account {
+    AccountBuilder::new(id).created_at(create_date).build()
+}
+
+#[test]
+fn last_cutoff_is_not_in_the_future() {
+    let cutoff = Duration::from_secs(22 * 60 * 60);
+    let day = 20_000 * DAY_MS;
+
+    assert_eq!(
+        get_last_eod_cutoff(day + 23 * 60 * 60 * 1000, cutoff),
+        day + 22 * 60 * 60 * 1000
+    );
+    assert_eq!(
+        get_last_eod_cutoff(day + 60 * 60 * 1000, cutoff),
+        day - 2 * 60 * 60 * 1000
+    );
+    assert_eq!(
+        get_last_eod_cutoff(day + 22 * 60 * 60 * 1000, cutoff),
+        day + 22 * 60 * 60 * 1000
+    );
+}
+
+#[tokio::test]
+async fn closing_balances_are_captured_once_and_served() {
+    let settings = SettingsModel {
+        eod_cutoff_utc: Some("22:00".to_string()),
+        ..settings()
+    };
+    let (app, events_publisher) = app(
+        settings,
+        vec![account("account-1", 1_000), account("account-2", 5_000)],
+    )
+    .await;
+    let telemetry = MyTelemetryContext::new();
+
+    // Only the first account existed at the first cut-off.
+    capture_daily_balances(&app, 2_000, &telemetry)
+        .await
+        .unwrap();
+    capture_daily_balances(&app, 2_000, &telemetry)
+        .await
+        .unwrap();
+
+    // Balances the bus does not take stay queued until it is back.
+    events_publisher.set_fail(true).await;
+    capture_daily_balances(&app, 6_000, &telemetry)
+        .await
+        .unwrap();
+    assert_eq!(events_publisher.daily_balances_events.lock().await.len(), 1);
+    assert_eq!(app.sb_events_outbox.get_queued_count(), 1);
+    events_publisher.set_fail(false).await;
+    app.sb_events_outbox.flush(&telemetry).await.unwrap();
+
+    let events = events_publisher.daily_balances_events.lock().await;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].0, "live");
+    assert_eq!(events[0].1.date_time_unix_ms, 2_000);
+    assert_eq!(events[0].1.balances.len(), 1);
+    assert_eq!(events[0].1.chunks_count, 1);
+    assert_eq!(events[1].1.balances.len(), 2);
+    drop(events);
+
+    let service = GrpcService::new(app);
+    let get_daily_balances = |account_id: Option<&str>, date_from, date_to| {
+        service.get_daily_balances(Request::new(AccountManagerGetDailyBalancesGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account_id.map(|x| x.to_string()),
+            account_type: "".to_string(),
+            date_from,
+            date_to,
+        }))
+    };
+
+    let balances: Vec<_> = get_daily_balances(None, 0, 10_000)
+        .await
+        .unwrap()
+        .into_inner()
+        .map(|x| x.unwrap())
+        .collect()
+        .await;
+    assert_eq!(balances.len(), 3);
+    assert_eq!(balances[0].date, 2_000);
+    assert_eq!(balances[0].balance, 100.0);
+
+    let balances: Vec<_> = get_daily_balances(Some("account-2"), 0, 10_000)
+        .await
+        .unwrap()
+        .into_inner()
+        .map(|x| x.unwrap())
+        .collect()
+        .await;
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances[0].date, 6_000);
+
+    let status = get_daily_balances(None, 10_000, 0).await.err().unwrap();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn closing_balances_are_published_in_chunks() {
+    let accounts = (0..DAILY_BALANCES_CHUNK_SIZE + 1)
+        .map(|x| account(&format!("account-{}", x), 1_000))
+        .collect();
+    let (app, events_publisher) = app(settings(), accounts).await;
+
+    capture_daily_balances(&app, 2_000, &MyTelemetryContext::new())
+        .await
+        .unwrap();
+
+    let events = events_publisher.daily_balances_events.lock().await;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].1.balances.len(), DAILY_BALANCES_CHUNK_SIZE);
+    assert_eq!((events[0].1.chunk, events[0].1.chunks_count), (0, 2));
+    assert_eq!(events[1].1.balances.len(), 1);
+    assert_eq!((events[1].1.chunk, events[1].1.chunks_count), (1, 2));
+}
+
+#[tokio::test]
+async fn balances_before_the_retained_history_are_refused() {
+    let (app, events_publisher) = app(settings(), vec![account("account", 1_000)]).await;
+    let accounts_cache = app.accounts_caches.get("").unwrap();
+    let telemetry = MyTelemetryContext::new();
+
+    capture_daily_balances(&app, 2_000, &telemetry)
+        .await
+        .unwrap();
+
+    let service = GrpcService::new(app.clone());
+    service
+        .update_client_account_balance(Request::new(common::update_balance_request(
+            10.0,
+            UpdateBalanceReason::Deposit,
+            "deposit",
+        )))
+        .await
+        .unwrap();
+    accounts_cache.prune_ledger(u64::MAX).await;
+
+    // The account existed at both cut-offs, so it is not left out silently.
+    assert!(matches!(
+        capture_daily_balances(&app, 3_000, &telemetry).await,
+        Err(OperationError::InvalidRequest(_))
+    ));
+    assert_eq!(events_publisher.daily_balances_events.lock().await.len(), 1);
+
+    let status = service
+        .get_daily_balances(Request::new(AccountManagerGetDailyBalancesGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: None,
+            account_type: "".to_string(),
+            date_from: 0,
+            date_to: 10_000,
+        }))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
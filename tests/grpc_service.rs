@@ -0,0 +1,950 @@
+mod common;
+
+use std::time::Duration;
+
+use accounts_manager::accounts_manager::accounts_manager_grpc_service_server::AccountsManagerGrpcService;
+use accounts_manager::accounts_manager::*;
+use accounts_manager::{BalanceRules, GrpcService, SettingsModel};
+use common::{service, service_with, settings, AccountBuilder};
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio_stream::StreamExt;
+use tonic::Request;
+
+async fn create_account(service: &GrpcService, trader_id: &str) -> AccountGrpcModel {
+    service
+        .create_account(Request::new(AccountManagerCreateAccountGrpcRequest {
+            trader_id: trader_id.to_string(),
+            currency: "USD".to_string(),
+            process_id: format!("create-{}", trader_id),
+            trading_group_id: None,
+            metadata: vec![],
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+}
+
+fn update_balance_request(
+    account: &AccountGrpcModel,
+    delta: f64,
+    process_id: &str,
+) -> AccountManagerUpdateAccountBalanceGrpcRequest {
+    AccountManagerUpdateAccountBalanceGrpcRequest {
+        trader_id: account.trader_id.clone(),
+        account_id: account.id.clone(),
+        ..common::update_balance_request(delta, UpdateBalanceReason::Deposit, process_id)
+    }
+}
+
+async fn collect<T>(
+    stream: impl tokio_stream::Stream<Item = Result<T, tonic::Status>> + Unpin,
+) -> Vec<T> {
+    stream.map(|x| x.unwrap()).collect().await
+}
+
+#[tokio::test]
+async fn create_and_read_accounts() {
+    let (service, events_publisher) = service().await;
+
+    let account = create_account(&service, "trader").await;
+    assert_eq!(account.balance, 100.0);
+    assert_eq!(account.trading_group, "standard");
+    assert_eq!(account.account_type, "live");
+    assert_eq!(events_publisher.persist_events.lock().await.len(), 1);
+
+    let response = service
+        .get_client_account(Request::new(AccountManagerGetClientAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+    assert_eq!(response.account.unwrap().id, account.id);
+
+    let get_client_account = |account_id: &str| {
+        service.get_client_account(Request::new(AccountManagerGetClientAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account_id.to_string(),
+            account_type: "".to_string(),
+        }))
+    };
+
+    // A missing account is answered in the body, as before validation.
+    let response = get_client_account("missing").await.unwrap().into_inner();
+    assert_eq!(
+        response.result,
+        AccountsManagerOperationResult::AccountNotFound as i32
+    );
+
+    let status = get_client_account("").await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let accounts = service
+        .get_client_accounts(Request::new(AccountManagerGetClientAccountsGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(collect(accounts).await.len(), 1);
+
+    let response = service
+        .get_trader_id_by_account_id(Request::new(
+            AccountManagerGetTraderIdByAccountIdGrpcRequest {
+                account_id: account.id.clone(),
+                account_type: "".to_string(),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.trader_id.as_deref(), Some("trader"));
+
+    let accounts = service
+        .search(Request::new(SearchAccounts {
+            trader_ids: vec!["trader".to_string()],
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(collect(accounts).await.len(), 1);
+
+    let accounts = service
+        .get_trading_group_accounts(Request::new(AccountManagerGetAccountsByGroupGrpcRequest {
+            trading_group: "standard".to_string(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(collect(accounts).await.len(), 1);
+}
+
+#[tokio::test]
+async fn create_account_returns_operation_id_on_error() {
+    let (service, _) = service().await;
+
+    let status = service
+        .create_account(Request::new(AccountManagerCreateAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            currency: "".to_string(),
+            process_id: "create-trader".to_string(),
+            trading_group_id: None,
+            metadata: vec![],
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap_err();
+
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    assert!(status.metadata().get("operation-id").is_some());
+
+    let response = service
+        .create_account(Request::new(AccountManagerCreateAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            currency: "USD".to_string(),
+            process_id: "create-trader".to_string(),
+            trading_group_id: None,
+            metadata: vec![],
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert!(response.operation_id.is_some());
+}
+
+#[tokio::test]
+async fn account_types_are_isolated() {
+    let settings = SettingsModel {
+        account_types: Some(vec!["demo".to_string()]),
+        ..settings()
+    };
+    let (service, _) = service_with(settings, vec![]).await;
+    create_account(&service, "trader").await;
+
+    let accounts = service
+        .get_client_accounts(Request::new(AccountManagerGetClientAccountsGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_type: "demo".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(collect(accounts).await.len(), 0);
+
+    let error = service
+        .get_client_accounts(Request::new(AccountManagerGetClientAccountsGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_type: "unknown".to_string(),
+        }))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+    let error = service
+        .get_client_account(Request::new(AccountManagerGetClientAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: "account".to_string(),
+            account_type: "unknown".to_string(),
+        }))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn update_balance_rejects_duplicates_and_insufficient_balance() {
+    let (service, _) = service().await;
+    let account = create_account(&service, "trader").await;
+
+    let response = service
+        .update_client_account_balance(Request::new(update_balance_request(
+            &account, 50.0, "deposit",
+        )))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+    assert_eq!(
+        response
+            .update_balance_info
+            .unwrap()
+            .account
+            .unwrap()
+            .balance,
+        150.0
+    );
+
+    let response = service
+        .update_client_account_balance(Request::new(update_balance_request(
+            &account, 50.0, "deposit",
+        )))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        response.result,
+        AccountsManagerOperationResult::ProcessIdDuplicate as i32
+    );
+
+    let response = service
+        .update_client_account_balance(Request::new(update_balance_request(
+            &account,
+            -1000.0,
+            "withdrawal",
+        )))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        response.result,
+        AccountsManagerOperationResult::NotEnoughBalance as i32
+    );
+
+    let response = service
+        .get_client_account(Request::new(AccountManagerGetClientAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.account.unwrap().balance, 150.0);
+}
+
+#[tokio::test]
+async fn unpublished_events_are_retried_in_order() {
+    let (app, events_publisher) = common::app(settings(), vec![]).await;
+    let service = GrpcService::new(app.clone());
+    let account = create_account(&service, "trader").await;
+
+    events_publisher.set_fail(true).await;
+
+    for (delta, process_id) in [(50.0, "deposit-1"), (20.0, "deposit-2")] {
+        let response = service
+            .update_client_account_balance(Request::new(update_balance_request(
+                &account, delta, process_id,
+            )))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+    }
+
+    assert_eq!(app.sb_events_outbox.get_queued_count(), 2);
+    assert!(app
+        .sb_events_outbox
+        .flush(&MyTelemetryContext::new())
+        .await
+        .is_err());
+
+    events_publisher.set_fail(false).await;
+    app.sb_events_outbox
+        .flush(&MyTelemetryContext::new())
+        .await
+        .unwrap();
+    assert_eq!(app.sb_events_outbox.get_queued_count(), 0);
+
+    let balances: Vec<f64> = events_publisher
+        .persist_events
+        .lock()
+        .await
+        .iter()
+        .filter_map(|x| x.1.update_account_event.as_ref())
+        .map(|x| x.account_after_update.as_ref().unwrap().balance)
+        .collect();
+    assert_eq!(balances, vec![150.0, 170.0]);
+}
+
+#[tokio::test]
+async fn account_settings_updates_are_published_and_audited() {
+    let (service, events_publisher) = service().await;
+    let account = create_account(&service, "trader").await;
+
+    let response = service
+        .update_account_trading_disabled(Request::new(
+            AccountManagerUpdateTradingDisabledGrpcRequest {
+                trader_id: "trader".to_string(),
+                account_id: account.id.clone(),
+                trading_disabled: true,
+                process_id: "disable".to_string(),
+                reason: TradingDisabledReason::Compliance as i32,
+                comment: None,
+                account_type: "".to_string(),
+                expected_version: None,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+    assert!(response.account.unwrap().trading_disabled);
+
+    let response = service
+        .update_account_trading_group(Request::new(AccountManagerUpdateTradingGroupGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            new_trading_group: "vip".to_string(),
+            process_id: "group".to_string(),
+            account_type: "".to_string(),
+            expected_version: None,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+    assert_eq!(response.previous_trading_group.as_deref(), Some("standard"));
+
+    let response = service
+        .update_account_metadata(Request::new(
+            AccountManagerUpdateAccountMetadataGrpcRequest {
+                trader_id: "trader".to_string(),
+                account_id: account.id.clone(),
+                metadata: vec![AccountMetadataItemGrpcModel {
+                    key: "desk".to_string(),
+                    value: "eu".to_string(),
+                }],
+                process_id: "metadata".to_string(),
+                account_type: "".to_string(),
+                expected_version: None,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.previous_metadata.len(), 0);
+
+    let status = service
+        .update_account_metadata(Request::new(
+            AccountManagerUpdateAccountMetadataGrpcRequest {
+                trader_id: "trader".to_string(),
+                account_id: "missing".to_string(),
+                metadata: vec![],
+                process_id: "metadata-missing".to_string(),
+                account_type: "".to_string(),
+                expected_version: None,
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+
+    assert_eq!(events_publisher.change_events.lock().await.len(), 3);
+
+    let persisted_fields: Vec<String> = events_publisher
+        .persist_events
+        .lock()
+        .await
+        .iter()
+        .flat_map(|(_, event)| event.audit_records.iter().map(|x| x.field.clone()))
+        .collect();
+    assert_eq!(
+        persisted_fields,
+        vec![
+            "trading_disabled",
+            "trading_disabled_reasons",
+            "trading_group",
+            "metadata"
+        ]
+    );
+
+    let records = service
+        .get_account_audit(Request::new(AccountManagerGetAccountAuditGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(collect(records).await.len() >= 3);
+}
+
+#[tokio::test]
+async fn unchanged_account_settings_are_not_applied() {
+    let (service, events_publisher) = service().await;
+    let account = create_account(&service, "trader").await;
+
+    let response = service
+        .update_account_trading_group(Request::new(AccountManagerUpdateTradingGroupGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            new_trading_group: account.trading_group.clone(),
+            process_id: "group".to_string(),
+            account_type: "".to_string(),
+            expected_version: Some(account.version),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+    assert_eq!(response.account.unwrap().version, account.version);
+
+    let response = service
+        .update_account_trading_disabled(Request::new(
+            AccountManagerUpdateTradingDisabledGrpcRequest {
+                trader_id: "trader".to_string(),
+                account_id: account.id.clone(),
+                trading_disabled: false,
+                process_id: "enable".to_string(),
+                reason: TradingDisabledReason::Compliance as i32,
+                comment: None,
+                account_type: "".to_string(),
+                expected_version: None,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.account.unwrap().version, account.version);
+
+    assert_eq!(events_publisher.change_events.lock().await.len(), 0);
+    assert_eq!(events_publisher.persist_events.lock().await.len(), 1);
+
+    let records = service
+        .get_account_audit(Request::new(AccountManagerGetAccountAuditGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(collect(records).await.len(), 0);
+}
+
+#[tokio::test]
+async fn stale_expected_version_is_rejected() {
+    let (service, _) = service().await;
+    let account = create_account(&service, "trader").await;
+    assert_eq!(account.version, 1);
+
+    let mut request = update_balance_request(&account, 10.0, "deposit-1");
+    request.expected_version = Some(1);
+    let response = service
+        .update_client_account_balance(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+    assert_eq!(
+        response
+            .update_balance_info
+            .unwrap()
+            .account
+            .unwrap()
+            .version,
+        2
+    );
+
+    let response = service
+        .update_account_trading_group(Request::new(AccountManagerUpdateTradingGroupGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            new_trading_group: "vip".to_string(),
+            process_id: "group".to_string(),
+            account_type: "".to_string(),
+            expected_version: Some(1),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        response.result,
+        AccountsManagerOperationResult::Conflict as i32
+    );
+}
+
+#[tokio::test]
+async fn balance_is_returned_at_a_point_in_time() {
+    let (service, _) = service().await;
+    let account = create_account(&service, "trader").await;
+
+    service
+        .update_client_account_balance(Request::new(update_balance_request(
+            &account, 25.0, "deposit",
+        )))
+        .await
+        .unwrap();
+
+    let get_balance_at = |date: u64| {
+        service.get_balance_at(Request::new(AccountManagerGetBalanceAtGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+            date,
+        }))
+    };
+
+    let response = get_balance_at(u64::MAX).await.unwrap().into_inner();
+    assert_eq!(response.balance, 125.0);
+    let last_operation = response.last_operation.unwrap();
+    assert_eq!(last_operation.delta, 25.0);
+    assert_eq!(last_operation.process_id, "deposit");
+
+    let status = get_balance_at(1).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+
+    let status = get_balance_at(0).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn statement_is_streamed_and_exported() {
+    let (service, _) = service().await;
+    let account = create_account(&service, "trader").await;
+
+    let mut request = update_balance_request(&account, 25.0, "deposit");
+    request.reference_transaction_id = Some("ref".to_string());
+    service
+        .update_client_account_balance(Request::new(request))
+        .await
+        .unwrap();
+
+    let rows = collect(
+        service
+            .get_statement(Request::new(AccountManagerGetStatementGrpcRequest {
+                trader_id: "trader".to_string(),
+                account_id: account.id.clone(),
+                account_type: "".to_string(),
+                date_from: 0,
+                date_to: u64::MAX,
+            }))
+            .await
+            .unwrap()
+            .into_inner(),
+    )
+    .await;
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].row_type, AccountStatementRowType::Opening as i32);
+    assert_eq!(rows[0].balance, 100.0);
+    assert_eq!(rows[1].reference_transaction_id.as_deref(), Some("ref"));
+    assert_eq!(rows[2].row_type, AccountStatementRowType::Closing as i32);
+    assert_eq!(rows[2].balance, 125.0);
+
+    let export = |trader_id: &str, format: AccountStatementFormat| {
+        service.export_statement(Request::new(AccountManagerExportStatementGrpcRequest {
+            trader_id: trader_id.to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+            date_from: 0,
+            date_to: u64::MAX,
+            format: format as i32,
+        }))
+    };
+
+    let response = export("trader", AccountStatementFormat::Csv)
+        .await
+        .unwrap()
+        .into_inner();
+    let csv = String::from_utf8(response.content).unwrap();
+    assert!(csv.contains(",Deposit,25,125,comment,ref,deposit,"));
+
+    let response = export("trader", AccountStatementFormat::Json)
+        .await
+        .unwrap()
+        .into_inner();
+    let json: serde_json::Value = serde_json::from_slice(&response.content).unwrap();
+    assert_eq!(json["closing_balance"], 125.0);
+
+    let status = export("other", AccountStatementFormat::Csv)
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn bonus_money_is_not_withdrawable() {
+    let (service, _) = service().await;
+    let account = create_account(&service, "trader").await;
+
+    let mut request = update_balance_request(&account, 50.0, "bonus");
+    request.reason = UpdateBalanceReason::Bonus as i32;
+    let response = service
+        .update_client_account_balance(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    let updated = response.update_balance_info.unwrap().account.unwrap();
+    assert_eq!(updated.balance, 150.0);
+    assert_eq!(updated.cash_balance, 100.0);
+    assert_eq!(updated.bonus_balance, 50.0);
+
+    let mut request = update_balance_request(&account, -120.0, "withdrawal");
+    request.reason = UpdateBalanceReason::Withdrawal as i32;
+    let response = service
+        .update_client_account_balance(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        response.result,
+        AccountsManagerOperationResult::NotEnoughBalance as i32
+    );
+
+    let mut request = update_balance_request(&account, -120.0, "loss");
+    request.reason = UpdateBalanceReason::TradingResult as i32;
+    let response = service
+        .update_client_account_balance(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    let updated = response.update_balance_info.unwrap().account.unwrap();
+    assert_eq!(updated.cash_balance, 0.0);
+    assert_eq!(updated.bonus_balance, 30.0);
+}
+
+#[tokio::test]
+async fn ensure_default_accounts_is_idempotent() {
+    let (service, _) = service().await;
+
+    let request = AccountManagerEnsureDefaultAccountsGrpcRequest {
+        trader_id: "trader".to_string(),
+        process_id: "ensure".to_string(),
+        account_type: "".to_string(),
+    };
+
+    let response = service
+        .ensure_default_accounts(Request::new(request.clone()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.created_account_ids.len(), 1);
+
+    let response = service
+        .ensure_default_accounts(Request::new(request.clone()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.created_account_ids.len(), 0);
+    assert_eq!(response.accounts.len(), 1);
+
+    // An account moved to another group still is the default one.
+    service
+        .update_account_trading_group(Request::new(AccountManagerUpdateTradingGroupGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: response.accounts[0].id.clone(),
+            new_trading_group: "vip".to_string(),
+            process_id: "move".to_string(),
+            account_type: "".to_string(),
+            expected_version: None,
+        }))
+        .await
+        .unwrap();
+
+    let response = service
+        .ensure_default_accounts(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.created_account_ids.len(), 0);
+    assert_eq!(response.accounts.len(), 1);
+
+    let status = service
+        .ensure_default_accounts(Request::new(
+            AccountManagerEnsureDefaultAccountsGrpcRequest {
+                trader_id: "".to_string(),
+                process_id: "ensure".to_string(),
+                account_type: "".to_string(),
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    assert!(status.metadata().get("operation-id").is_some());
+}
+
+#[tokio::test]
+async fn default_accounts_are_published_once_the_bus_is_back() {
+    let (app, events_publisher) = common::app(settings(), vec![]).await;
+    let service = GrpcService::new(app.clone());
+
+    events_publisher.set_fail(true).await;
+
+    let response = service
+        .ensure_default_accounts(Request::new(
+            AccountManagerEnsureDefaultAccountsGrpcRequest {
+                trader_id: "trader".to_string(),
+                process_id: "ensure".to_string(),
+                account_type: "".to_string(),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.created_account_ids.len(), 1);
+    assert_eq!(app.sb_events_outbox.get_queued_count(), 1);
+
+    events_publisher.set_fail(false).await;
+    app.sb_events_outbox
+        .flush(&MyTelemetryContext::new())
+        .await
+        .unwrap();
+
+    let events = events_publisher.persist_events.lock().await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].1.add_account_event.as_ref().unwrap().id,
+        response.created_account_ids[0]
+    );
+}
+
+#[tokio::test]
+async fn bulk_updates_support_dry_run() {
+    let (service, _) = service().await;
+    create_account(&service, "trader-1").await;
+    create_account(&service, "trader-2").await;
+
+    let filter = SearchAccounts {
+        trading_group: Some("standard".to_string()),
+        ..Default::default()
+    };
+
+    let response = service
+        .bulk_update_trading_group(Request::new(
+            AccountManagerBulkUpdateTradingGroupGrpcRequest {
+                filter: Some(filter.clone()),
+                new_trading_group: "vip".to_string(),
+                process_id: "bulk-group".to_string(),
+                dry_run: true,
+                all_accounts: false,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.affected_count, 2);
+
+    let response = service
+        .bulk_update_trading_disabled(Request::new(
+            AccountManagerBulkUpdateTradingDisabledGrpcRequest {
+                filter: Some(filter.clone()),
+                trading_disabled: true,
+                process_id: "bulk-disabled".to_string(),
+                dry_run: false,
+                reason: TradingDisabledReason::RiskMarginCall as i32,
+                comment: None,
+                all_accounts: false,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.affected_count, 2);
+
+    let accounts = service
+        .search(Request::new(SearchAccounts {
+            disabled: Some(true),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(collect(accounts).await.len(), 2);
+
+    let status = service
+        .bulk_update_trading_group(Request::new(
+            AccountManagerBulkUpdateTradingGroupGrpcRequest {
+                filter: None,
+                new_trading_group: "vip".to_string(),
+                process_id: "bulk-group".to_string(),
+                dry_run: true,
+                all_accounts: false,
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn subscription_starts_with_a_snapshot() {
+    let (service, _) = service().await;
+    let account = create_account(&service, "trader").await;
+
+    let mut updates = service
+        .subscribe_account_updates(Request::new(
+            AccountManagerSubscribeAccountUpdatesGrpcRequest {
+                trader_ids: vec!["trader".to_string()],
+                trading_group: None,
+                account_type: "".to_string(),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let snapshot = updates.next().await.unwrap().unwrap();
+    assert_eq!(snapshot.id, account.id);
+
+    service
+        .update_client_account_balance(Request::new(update_balance_request(
+            &account, 10.0, "deposit",
+        )))
+        .await
+        .unwrap();
+
+    let update = updates.next().await.unwrap().unwrap();
+    assert_eq!(update.balance, 110.0);
+}
+
+#[tokio::test]
+async fn subscription_sends_accounts_leaving_the_filter() {
+    let (service, _) = service().await;
+    let account = create_account(&service, "trader").await;
+
+    let mut updates = service
+        .subscribe_account_updates(Request::new(
+            AccountManagerSubscribeAccountUpdatesGrpcRequest {
+                trader_ids: vec![],
+                trading_group: Some(account.trading_group.clone()),
+                account_type: "".to_string(),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let snapshot = updates.next().await.unwrap().unwrap();
+    assert_eq!(snapshot.id, account.id);
+
+    service
+        .update_account_trading_group(Request::new(AccountManagerUpdateTradingGroupGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            new_trading_group: "vip".to_string(),
+            process_id: "group".to_string(),
+            account_type: "".to_string(),
+            expected_version: None,
+        }))
+        .await
+        .unwrap();
+
+    let update = updates.next().await.unwrap().unwrap();
+    assert_eq!(update.id, account.id);
+    assert_eq!(update.trading_group, "vip");
+
+    service
+        .update_client_account_balance(Request::new(update_balance_request(
+            &account, 10.0, "deposit",
+        )))
+        .await
+        .unwrap();
+
+    let next = tokio::time::timeout(Duration::from_millis(100), updates.next()).await;
+    assert!(next.is_err());
+}
+
+#[tokio::test]
+async fn lagging_subscriber_gets_a_fresh_snapshot() {
+    let (app, _) = common::app(settings(), vec![AccountBuilder::new("account").build()]).await;
+    let service = GrpcService::new(app.clone());
+    let accounts_cache = app.accounts_caches.get("").unwrap();
+
+    let mut updates = service
+        .subscribe_account_updates(Request::new(
+            AccountManagerSubscribeAccountUpdatesGrpcRequest {
+                trader_ids: vec!["trader".to_string()],
+                trading_group: None,
+                account_type: "".to_string(),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+
+    // More updates than the broadcast and the subscriber buffer hold together.
+    let updates_count = 12_000;
+
+    for index in 0..updates_count {
+        accounts_cache
+            .update_balance(
+                &common::update_balance_request(
+                    1.0,
+                    UpdateBalanceReason::Deposit,
+                    &format!("deposit-{}", index),
+                ),
+                &BalanceRules::default(),
+                Duration::from_secs(60),
+                &format!("operation-{}", index),
+                |_, _, _| vec![],
+            )
+            .await
+            .unwrap();
+    }
+
+    let final_balance = 100.0 + updates_count as f64;
+    let mut received = 0;
+
+    loop {
+        let update = tokio::time::timeout(Duration::from_secs(5), updates.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        received += 1;
+
+        if update.balance == final_balance {
+            break;
+        }
+    }
+
+    assert!(received < updates_count);
+}
+
+#[tokio::test]
+async fn ping() {
+    let (service, _) = service().await;
+    assert!(service.ping(Request::new(())).await.is_ok());
+}
@@ -0,0 +1,291 @@
+mod common;
+
+use std::{path::Path, sync::Arc};
+
+use accounts_manager::accounts_manager::accounts_manager_grpc_service_server::AccountsManagerGrpcService;
+use accounts_manager::accounts_manager::*;
+use accounts_manager::accounts_manager_persistence::accounts_manager_persistence_grpc_service_server::AccountsManagerPersistenceGrpcService;
+use accounts_manager::accounts_manager_persistence::GetAllAccountsGrpcRequest;
+use accounts_manager::{
+    capture_daily_balances, AppContext, DailyBalancesSbEvent, FileAccountsStore, GrpcService,
+    InMemorySettingsSource, LocalEventsPublisher, LocalPersistenceGrpcService,
+};
+use common::settings;
+use service_sdk::my_telemetry::MyTelemetryContext;
+use tokio::task::JoinHandle;
+use tokio_stream::StreamExt;
+use tonic::Request;
+
+/// Starts the app on top of the file store. The returned handle completes
+/// once the app is dropped and every persist event reached the file.
+async fn start(path: &Path) -> (Arc<AppContext>, Arc<FileAccountsStore>, JoinHandle<()>) {
+    let store = Arc::new(FileAccountsStore::open(path).await.unwrap());
+    let (events_publisher, persist_events) = LocalEventsPublisher::new();
+
+    let app = AppContext::new(
+        Arc::new(InMemorySettingsSource::new(settings())),
+        store.as_ref(),
+        Arc::new(events_publisher),
+    )
+    .await;
+
+    let consumer = tokio::spawn({
+        let store = store.clone();
+        async move { store.consume(persist_events).await }
+    });
+
+    (Arc::new(app), store, consumer)
+}
+
+#[tokio::test]
+async fn accounts_survive_restart() {
+    let path = std::env::temp_dir().join(format!("accounts-{}.json", uuid::Uuid::new_v4()));
+
+    let (app, _, consumer) = start(&path).await;
+    let service = GrpcService::new(app);
+
+    let account = service
+        .create_account(Request::new(AccountManagerCreateAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            currency: "USD".to_string(),
+            process_id: "create".to_string(),
+            trading_group_id: None,
+            metadata: vec![],
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let response = service
+        .update_client_account_balance(Request::new(
+            AccountManagerUpdateAccountBalanceGrpcRequest {
+                trader_id: "trader".to_string(),
+                account_id: account.id.clone(),
+                delta: 50.0,
+                comment: "comment".to_string(),
+                process_id: "deposit".to_string(),
+                allow_negative_balance: false,
+                reason: UpdateBalanceReason::Deposit as i32,
+                reference_transaction_id: None,
+                same_response_process_id: false,
+                account_type: "".to_string(),
+                expected_version: None,
+                bonus_expires_at: None,
+                bonus_turnover_required: None,
+                trading_volume: None,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+
+    let response = service
+        .update_account_trading_disabled(Request::new(
+            AccountManagerUpdateTradingDisabledGrpcRequest {
+                trader_id: "trader".to_string(),
+                account_id: account.id.clone(),
+                trading_disabled: true,
+                process_id: "compliance".to_string(),
+                reason: TradingDisabledReason::Compliance as i32,
+                comment: Some("kyc".to_string()),
+                account_type: "".to_string(),
+                expected_version: None,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result, AccountsManagerOperationResult::Ok as i32);
+
+    drop(service);
+    consumer.await.unwrap();
+
+    let (app, store, _) = start(&path).await;
+    let service = GrpcService::new(app);
+
+    let response = service
+        .get_client_account(Request::new(AccountManagerGetClientAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let reloaded = response.account.unwrap();
+    assert_eq!(reloaded.balance, 150.0);
+    assert_eq!(reloaded.version, 3);
+    assert!(reloaded.trading_disabled);
+    assert_eq!(reloaded.trading_disabled_reasons.len(), 1);
+    assert_eq!(
+        reloaded.trading_disabled_reasons[0].reason,
+        TradingDisabledReason::Compliance as i32
+    );
+    assert_eq!(
+        reloaded.trading_disabled_reasons[0].comment,
+        Some("kyc".to_string())
+    );
+
+    let audit: Vec<_> = service
+        .get_account_audit(Request::new(AccountManagerGetAccountAuditGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .collect()
+        .await;
+    let audit: Vec<_> = audit.into_iter().map(|x| x.unwrap().field).collect();
+    assert_eq!(audit, vec!["trading_disabled", "trading_disabled_reasons"]);
+
+    // The ledger is reloaded rather than restarted from the snapshot.
+    let response = service
+        .get_balance_at(Request::new(AccountManagerGetBalanceAtGrpcRequest {
+            trader_id: "trader".to_string(),
+            account_id: account.id.clone(),
+            account_type: "".to_string(),
+            date: chrono::offset::Utc::now().timestamp_millis() as u64,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let last_operation = response.last_operation.unwrap();
+    assert_eq!(last_operation.sequence, 1);
+    assert_eq!(
+        last_operation.reason,
+        Some(UpdateBalanceReason::Deposit as i32)
+    );
+
+    let response = service
+        .replay_ledger(Request::new(AccountManagerReplayLedgerGrpcRequest {
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.accounts_checked, 1);
+    assert!(response.mismatches.is_empty());
+
+    let accounts: Vec<_> = LocalPersistenceGrpcService::new(store)
+        .get_all_accounts(Request::new(GetAllAccountsGrpcRequest {
+            accounts_type: "live".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .collect()
+        .await;
+    assert_eq!(accounts.len(), 1);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn captured_cutoffs_survive_restart() {
+    let path = std::env::temp_dir().join(format!("accounts-{}.json", uuid::Uuid::new_v4()));
+    let telemetry = MyTelemetryContext::new();
+
+    let (app, _, consumer) = start(&path).await;
+    capture_daily_balances(&app, 2_000, &telemetry)
+        .await
+        .unwrap();
+    drop(app);
+    consumer.await.unwrap();
+
+    let (app, _, _) = start(&path).await;
+    assert!(app.daily_balances_cache.has_snapshot("live", 2_000).await);
+    assert!(!app.daily_balances_cache.has_snapshot("live", 3_000).await);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn open_bonus_grants_survive_restart() {
+    let path = std::env::temp_dir().join(format!("accounts-{}.json", uuid::Uuid::new_v4()));
+
+    let (app, _, consumer) = start(&path).await;
+    let service = GrpcService::new(app.clone());
+
+    let account = service
+        .create_account(Request::new(AccountManagerCreateAccountGrpcRequest {
+            trader_id: "trader".to_string(),
+            currency: "USD".to_string(),
+            process_id: "create".to_string(),
+            trading_group_id: None,
+            metadata: vec![],
+            account_type: "".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    for (delta, process_id) in [(50.0, "bonus-1"), (30.0, "bonus-2"), (-60.0, "debit")] {
+        let mut request =
+            common::update_balance_request(delta, UpdateBalanceReason::Bonus, process_id);
+        request.account_id = account.id.clone();
+
+        service
+            .update_client_account_balance(Request::new(request))
+            .await
+            .unwrap();
+    }
+
+    drop(service);
+    drop(app);
+    consumer.await.unwrap();
+
+    let (app, _, _) = start(&path).await;
+    let grants = app
+        .accounts_caches
+        .get("live")
+        .unwrap()
+        .get_bonus_grants(&account.id)
+        .await;
+    assert_eq!(grants.len(), 1);
+    assert_eq!(grants[0].amount, 20.0);
+    assert_eq!(grants[0].status, BonusGrantStatus::Active);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn store_is_written_through_a_temporary_file() {
+    let path = std::env::temp_dir().join(format!("accounts-{}.json", uuid::Uuid::new_v4()));
+
+    std::fs::write(&path, "not json").unwrap();
+    assert!(FileAccountsStore::open(&path).await.is_err());
+    std::fs::remove_file(&path).unwrap();
+
+    let store = FileAccountsStore::open(&path).await.unwrap();
+
+    // A cut-off is recorded with its last chunk only.
+    for chunk in 0..2 {
+        store
+            .apply_daily_balances(
+                "live",
+                &DailyBalancesSbEvent {
+                    date_time_unix_ms: 2_000,
+                    balances: vec![],
+                    chunk,
+                    chunks_count: 2,
+                },
+            )
+            .await
+            .unwrap();
+
+        if chunk == 0 {
+            assert!(store.get_daily_balances_cutoffs("live").await.is_empty());
+        }
+    }
+
+    assert!(!Path::new(&format!("{}.tmp", path.display())).exists());
+
+    let store = FileAccountsStore::open(&path).await.unwrap();
+    assert_eq!(store.get_daily_balances_cutoffs("live").await, vec![2_000]);
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+
+use accounts_manager::{RateLimitSettingsModel, RateLimiter, RateLimitsSettingsModel, TokenBucket};
+
+const SETTINGS: RateLimitSettingsModel = RateLimitSettingsModel {
+    requests_per_second: 10.0,
+    burst: 2.0,
+};
+
+#[test]
+fn bucket_allows_burst_then_rejects_with_retry_hint() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::new(&SETTINGS, now);
+
+    assert!(bucket.try_acquire(&SETTINGS, now).is_ok());
+    assert!(bucket.try_acquire(&SETTINGS, now).is_ok());
+
+    let retry_after = bucket.try_acquire(&SETTINGS, now).unwrap_err();
+    assert_eq!(retry_after.as_millis(), 100);
+}
+
+#[test]
+fn bucket_refills_over_time_up_to_burst() {
+    let now = Instant::now();
+    let mut bucket = TokenBucket::new(&SETTINGS, now);
+
+    assert!(bucket.try_acquire(&SETTINGS, now).is_ok());
+    assert!(bucket.try_acquire(&SETTINGS, now).is_ok());
+
+    let later = now + Duration::from_millis(100);
+    assert!(bucket.try_acquire(&SETTINGS, later).is_ok());
+    assert!(bucket.try_acquire(&SETTINGS, later).is_err());
+
+    let much_later = later + Duration::from_secs(60);
+    assert!(bucket.is_full(&SETTINGS, much_later));
+    assert!(bucket.try_acquire(&SETTINGS, much_later).is_ok());
+    assert!(bucket.try_acquire(&SETTINGS, much_later).is_ok());
+    assert!(bucket.try_acquire(&SETTINGS, much_later).is_err());
+}
+
+#[tokio::test]
+async fn request_rejected_by_one_limit_does_not_use_the_other() {
+    let rate_limiter = RateLimiter::new();
+    let settings = RateLimitsSettingsModel {
+        per_trader: Some(RateLimitSettingsModel {
+            requests_per_second: 0.0,
+            burst: 1.0,
+        }),
+        per_caller: Some(RateLimitSettingsModel {
+            requests_per_second: 0.0,
+            burst: 2.0,
+        }),
+    };
+
+    assert!(rate_limiter
+        .check("caller", Some("trader-1"), &settings)
+        .await
+        .is_ok());
+    assert!(rate_limiter
+        .check("caller", Some("trader-1"), &settings)
+        .await
+        .is_err());
+
+    // The caller still has the token the rejected request did not take.
+    assert!(rate_limiter
+        .check("caller", Some("trader-2"), &settings)
+        .await
+        .is_ok());
+
+    // A request rejected by the caller limit leaves the trader one intact.
+    assert!(rate_limiter
+        .check("caller", Some("trader-3"), &settings)
+        .await
+        .is_err());
+    assert!(rate_limiter
+        .check("other-caller", Some("trader-3"), &settings)
+        .await
+        .is_ok());
+}
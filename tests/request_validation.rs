@@ -0,0 +1,297 @@
+use accounts_manager::accounts_manager::{
+    AccountManagerBulkUpdateTradingGroupGrpcRequest, AccountManagerCreateAccountGrpcRequest,
+    AccountManagerEnsureDefaultAccountsGrpcRequest, AccountManagerGetAccountAuditGrpcRequest,
+    AccountManagerGetAccountsByGroupGrpcRequest, AccountManagerGetClientAccountGrpcRequest,
+    AccountManagerGetClientAccountsGrpcRequest, AccountManagerGetTraderIdByAccountIdGrpcRequest,
+    AccountManagerReplayLedgerGrpcRequest, AccountManagerUpdateAccountBalanceGrpcRequest,
+    AccountManagerUpdateTradingDisabledGrpcRequest, AccountManagerUpdateTradingGroupGrpcRequest,
+    AccountMetadataItemGrpcModel, AccountsManagerOperationResult, FromToInt64Model, SearchAccounts,
+    TradingDisabledReason, UpdateBalanceReason,
+};
+use accounts_manager::{OperationError, ValidateRequest};
+
+fn assert_invalid(result: Result<(), OperationError>, expected_message_part: &str) {
+    match result {
+        Ok(_) => panic!(
+            "Expected InvalidRequest containing '{}'",
+            expected_message_part
+        ),
+        Err(error) => {
+            assert_eq!(
+                error.as_grpc_error(),
+                AccountsManagerOperationResult::InvalidRequest as i32
+            );
+            assert!(
+                error.get_message().contains(expected_message_part),
+                "'{}' does not contain '{}'",
+                error.get_message(),
+                expected_message_part
+            );
+        }
+    }
+}
+
+fn create_account_request() -> AccountManagerCreateAccountGrpcRequest {
+    AccountManagerCreateAccountGrpcRequest {
+        trader_id: "trader".to_string(),
+        currency: "USD".to_string(),
+        process_id: "process".to_string(),
+        trading_group_id: None,
+        metadata: vec![],
+        account_type: "".to_string(),
+    }
+}
+
+fn update_balance_request() -> AccountManagerUpdateAccountBalanceGrpcRequest {
+    AccountManagerUpdateAccountBalanceGrpcRequest {
+        trader_id: "trader".to_string(),
+        account_id: "account".to_string(),
+        delta: 10.0,
+        comment: "comment".to_string(),
+        process_id: "process".to_string(),
+        allow_negative_balance: false,
+        reason: UpdateBalanceReason::Deposit as i32,
+        reference_transaction_id: None,
+        same_response_process_id: false,
+        account_type: "".to_string(),
+        expected_version: None,
+        bonus_expires_at: None,
+        bonus_turnover_required: None,
+        trading_volume: None,
+    }
+}
+
+#[test]
+fn create_account_validation() {
+    assert!(create_account_request().validate().is_ok());
+
+    let mut request = create_account_request();
+    request.trader_id = "".to_string();
+    assert_invalid(request.validate(), "trader_id");
+
+    let mut request = create_account_request();
+    request.currency = " ".to_string();
+    assert_invalid(request.validate(), "currency");
+
+    let mut request = create_account_request();
+    request.process_id = "".to_string();
+    assert_invalid(request.validate(), "process_id");
+
+    let mut request = create_account_request();
+    request.trading_group_id = Some("".to_string());
+    assert_invalid(request.validate(), "trading_group_id");
+
+    let mut request = create_account_request();
+    request.metadata = vec![
+        AccountMetadataItemGrpcModel {
+            key: "key".to_string(),
+            value: "1".to_string(),
+        },
+        AccountMetadataItemGrpcModel {
+            key: "key".to_string(),
+            value: "2".to_string(),
+        },
+    ];
+    assert_invalid(request.validate(), "duplicated");
+}
+
+#[test]
+fn update_balance_validation() {
+    assert!(update_balance_request().validate().is_ok());
+
+    for delta in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let mut request = update_balance_request();
+        request.delta = delta;
+        assert_invalid(request.validate(), "finite");
+    }
+
+    let mut request = update_balance_request();
+    request.delta = 0.0;
+    assert_invalid(request.validate(), "zero");
+
+    let mut request = update_balance_request();
+    request.reason = 1000;
+    assert_invalid(request.validate(), "reason");
+
+    let mut request = update_balance_request();
+    request.account_id = "".to_string();
+    assert_invalid(request.validate(), "account_id");
+
+    let mut request = update_balance_request();
+    request.process_id = "".to_string();
+    assert_invalid(request.validate(), "process_id");
+}
+
+#[test]
+fn ensure_default_accounts_validation() {
+    let request = AccountManagerEnsureDefaultAccountsGrpcRequest {
+        trader_id: "trader".to_string(),
+        process_id: "process".to_string(),
+        account_type: "".to_string(),
+    };
+    assert!(request.validate().is_ok());
+
+    let request = AccountManagerEnsureDefaultAccountsGrpcRequest {
+        trader_id: "".to_string(),
+        process_id: "process".to_string(),
+        account_type: "".to_string(),
+    };
+    assert_invalid(request.validate(), "trader_id");
+}
+
+#[test]
+fn read_requests_validation() {
+    let request = AccountManagerGetClientAccountGrpcRequest {
+        trader_id: "trader".to_string(),
+        account_id: "".to_string(),
+        account_type: "".to_string(),
+    };
+    assert_invalid(request.validate(), "account_id");
+
+    let request = AccountManagerGetClientAccountsGrpcRequest {
+        trader_id: "".to_string(),
+        account_type: "".to_string(),
+    };
+    assert_invalid(request.validate(), "trader_id");
+
+    let request = AccountManagerGetTraderIdByAccountIdGrpcRequest {
+        account_id: "".to_string(),
+        account_type: "".to_string(),
+    };
+    assert_invalid(request.validate(), "account_id");
+
+    let request = AccountManagerGetAccountsByGroupGrpcRequest {
+        trading_group: "".to_string(),
+        account_type: "".to_string(),
+    };
+    assert_invalid(request.validate(), "trading_group");
+}
+
+#[test]
+fn search_validation() {
+    assert!(SearchAccounts::default().validate().is_ok());
+
+    let request = SearchAccounts {
+        balance: Some(FromToInt64Model {
+            from: Some(100),
+            to: Some(10),
+        }),
+        ..Default::default()
+    };
+    assert_invalid(request.validate(), "balance.from");
+
+    let request = SearchAccounts {
+        trader_ids: vec!["".to_string()],
+        ..Default::default()
+    };
+    assert_invalid(request.validate(), "trader_ids");
+}
+
+#[test]
+fn trading_settings_validation() {
+    let request = AccountManagerUpdateTradingDisabledGrpcRequest {
+        trader_id: "trader".to_string(),
+        account_id: "account".to_string(),
+        trading_disabled: true,
+        process_id: "".to_string(),
+        reason: TradingDisabledReason::Compliance as i32,
+        comment: None,
+        account_type: "".to_string(),
+        expected_version: None,
+    };
+    assert_invalid(request.validate(), "process_id");
+
+    let request = AccountManagerUpdateTradingDisabledGrpcRequest {
+        trader_id: "trader".to_string(),
+        account_id: "account".to_string(),
+        trading_disabled: true,
+        process_id: "process".to_string(),
+        reason: 1000,
+        comment: None,
+        account_type: "".to_string(),
+        expected_version: None,
+    };
+    assert_invalid(request.validate(), "reason");
+
+    let request = AccountManagerUpdateTradingGroupGrpcRequest {
+        trader_id: "trader".to_string(),
+        account_id: "account".to_string(),
+        new_trading_group: "".to_string(),
+        process_id: "process".to_string(),
+        account_type: "".to_string(),
+        expected_version: None,
+    };
+    assert_invalid(request.validate(), "new_trading_group");
+}
+
+#[test]
+fn bulk_update_validation() {
+    let request = AccountManagerBulkUpdateTradingGroupGrpcRequest {
+        filter: Some(SearchAccounts {
+            trading_group: Some("standard".to_string()),
+            ..Default::default()
+        }),
+        new_trading_group: "standard-v2".to_string(),
+        process_id: "process".to_string(),
+        dry_run: true,
+        all_accounts: false,
+    };
+    assert!(request.validate().is_ok());
+
+    let request = AccountManagerBulkUpdateTradingGroupGrpcRequest {
+        filter: None,
+        new_trading_group: "standard-v2".to_string(),
+        process_id: "process".to_string(),
+        dry_run: false,
+        all_accounts: false,
+    };
+    assert_invalid(request.validate(), "filter");
+
+    let mut request = AccountManagerBulkUpdateTradingGroupGrpcRequest {
+        filter: Some(SearchAccounts {
+            created: Some(FromToInt64Model {
+                from: None,
+                to: None,
+            }),
+            ..Default::default()
+        }),
+        new_trading_group: "standard-v2".to_string(),
+        process_id: "process".to_string(),
+        dry_run: false,
+        all_accounts: false,
+    };
+    assert_invalid(request.validate(), "all_accounts");
+
+    request.all_accounts = true;
+    assert!(request.validate().is_ok());
+}
+
+#[test]
+fn account_audit_validation() {
+    let request = AccountManagerGetAccountAuditGrpcRequest {
+        trader_id: "trader".to_string(),
+        account_id: "account".to_string(),
+        account_type: "".to_string(),
+    };
+    assert!(request.validate().is_ok());
+
+    let request = AccountManagerGetAccountAuditGrpcRequest {
+        trader_id: "trader".to_string(),
+        account_id: "".to_string(),
+        account_type: "".to_string(),
+    };
+    assert_invalid(request.validate(), "account_id");
+}
+
+#[test]
+fn replay_ledger_validation() {
+    let request = AccountManagerReplayLedgerGrpcRequest {
+        account_type: "".to_string(),
+    };
+    assert!(request.validate().is_ok());
+
+    let request = AccountManagerReplayLedgerGrpcRequest {
+        account_type: " ".to_string(),
+    };
+    assert_invalid(request.validate(), "account_type");
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use std::collections::HashMap;
+
+use accounts_manager::{
+    get_settings_changes, validate_settings, DefaultAccountSettingsModel, RateLimitSettingsModel,
+    RateLimitsSettingsModel,
+};
+use common::settings;
+
+#[test]
+fn valid_settings_pass() {
+    assert!(validate_settings(&settings()).is_ok());
+}
+
+#[test]
+fn default_account_currencies_are_unique() {
+    let template = |trading_group: &str| DefaultAccountSettingsModel {
+        currency: "USD".to_string(),
+        balance: None,
+        trading_group: Some(trading_group.to_string()),
+        metadata: None,
+    };
+    let mut settings = settings();
+    settings.default_accounts = Some(HashMap::from([(
+        "live".to_string(),
+        vec![template("standard"), template("pro")],
+    )]));
+
+    let errors = validate_settings(&settings).unwrap_err();
+    assert_eq!(
+        errors,
+        vec!["default_accounts.live.currency USD is listed more than once".to_string()]
+    );
+}
+
+#[test]
+fn invalid_defaults_are_reported_together() {
+    let mut settings = settings();
+    settings.default_account_balance = -1.0;
+    settings.default_account_trading_group = " ".to_string();
+    settings.default_accounts = Some(HashMap::from([(
+        "live".to_string(),
+        vec![DefaultAccountSettingsModel {
+            currency: "".to_string(),
+            balance: Some(f64::NAN),
+            trading_group: None,
+            metadata: None,
+        }],
+    )]));
+    settings.rate_limits = Some(RateLimitsSettingsModel {
+        per_trader: Some(RateLimitSettingsModel {
+            requests_per_second: 0.0,
+            burst: 10.0,
+        }),
+        per_caller: None,
+    });
+    settings.eod_cutoff_utc = Some("25:00".to_string());
+    settings.trading_loss_buckets = Some(vec!["cash".to_string(), "equity".to_string()]);
+    settings.bonus_expiration_days = Some(0);
+
+    let errors = validate_settings(&settings).unwrap_err();
+
+    for field in [
+        "default_account_balance",
+        "default_account_trading_group",
+        "default_accounts.live.currency",
+        "default_accounts.live.balance",
+        "rate_limits.per_trader.requests_per_second",
+        "eod_cutoff_utc",
+        "trading_loss_buckets",
+        "bonus_expiration_days",
+    ] {
+        assert!(
+            errors.iter().any(|x| x.starts_with(field)),
+            "{} not in {:?}",
+            field,
+            errors
+        );
+    }
+}
+
+#[test]
+fn changes_cover_balance_group_and_currency() {
+    let old = settings();
+    assert!(get_settings_changes(&old, &old).is_empty());
+
+    let mut new = settings();
+    new.default_account_balance = 5_000.0;
+    new.accounts_default_currency = Some("EUR".to_string());
+
+    let fields: Vec<&str> = get_settings_changes(&old, &new)
+        .iter()
+        .map(|x| x.field)
+        .collect();
+
+    assert_eq!(fields, vec!["default_account_balance", "default_accounts"]);
+}